@@ -0,0 +1,269 @@
+use itertools::Itertools;
+use std::{collections::BTreeMap, fmt};
+
+// how the sorted lists are paired up when they differ in length
+#[derive(PartialEq, Clone, Copy, Debug)]
+pub enum Pairing {
+    // both lists must be the same length
+    Strict,
+    // pairs up to the length of the shorter list, leaving the rest of the longer unpaired
+    Truncate,
+    // pads the shorter list with a fill value so every entry is paired
+    Pad(u32),
+}
+
+impl From<&str> for Pairing {
+    fn from(s: &str) -> Self {
+        match s.split_once('=') {
+            None if s == "strict" => Pairing::Strict,
+            None if s == "truncate" => Pairing::Truncate,
+            Some(("pad", fill)) => Pairing::Pad(
+                fill.trim()
+                    .parse()
+                    .unwrap_or_else(|_| panic!("Invalid pad value: {}", fill)),
+            ),
+            _ => panic!("Invalid pairing policy: {}", s),
+        }
+    }
+}
+
+// two lists compared by rank, the smallest of each paired together and so on
+pub struct ListComparison {
+    left: Vec<u32>,
+    right: Vec<u32>,
+    pairing: Pairing,
+}
+
+impl ListComparison {
+    pub fn new(left: Vec<u32>, right: Vec<u32>, pairing: Pairing) -> Self {
+        if pairing == Pairing::Strict && left.len() != right.len() {
+            panic!(
+                "Lists have different lengths ({} and {})",
+                left.len(),
+                right.len()
+            );
+        }
+        ListComparison {
+            left: left.into_iter().sorted().collect(),
+            right: right.into_iter().sorted().collect(),
+            pairing,
+        }
+    }
+
+    pub fn pairs(&self) -> Vec<(u32, u32)> {
+        match self.pairing {
+            Pairing::Strict | Pairing::Truncate => self
+                .left
+                .iter()
+                .copied()
+                .zip(self.right.iter().copied())
+                .collect(),
+            Pairing::Pad(fill) => self
+                .left
+                .iter()
+                .copied()
+                .zip_longest(self.right.iter().copied())
+                .map(|pair| pair.or(fill, fill))
+                .collect(),
+        }
+    }
+
+    // the entries of each list left over by the pairing, in sorted order
+    pub fn unpaired(&self) -> (&[u32], &[u32]) {
+        let paired = match self.pairing {
+            Pairing::Pad(_) => self.left.len().max(self.right.len()),
+            _ => self.left.len().min(self.right.len()),
+        };
+        (
+            &self.left[paired.min(self.left.len())..],
+            &self.right[paired.min(self.right.len())..],
+        )
+    }
+
+    pub fn distances(&self) -> Vec<u32> {
+        self.pairs().iter().map(|(a, b)| a.abs_diff(*b)).collect()
+    }
+
+    pub fn total_distance(&self) -> u32 {
+        self.distances().iter().sum()
+    }
+
+    // the p-th percentile of the pair distances, interpolating linearly between ranks
+    pub fn percentile_distance(&self, p: f64) -> Option<f64> {
+        if !(0.0..=100.0).contains(&p) {
+            panic!("Percentile must be between 0 and 100, got {}", p);
+        }
+        let distances = self.distances().into_iter().sorted().collect::<Vec<u32>>();
+        if distances.is_empty() {
+            return None;
+        }
+        let rank = p / 100.0 * (distances.len() - 1) as f64;
+        let (lower, upper) = (rank.floor() as usize, rank.ceil() as usize);
+        let fraction = rank - lower as f64;
+        Some(
+            distances[lower] as f64
+                + (distances[upper] as f64 - distances[lower] as f64) * fraction,
+        )
+    }
+
+    pub fn median_distance(&self) -> Option<f64> {
+        self.percentile_distance(50.0)
+    }
+
+    // how many times each value appears in the left and right lists
+    pub fn histogram(&self) -> BTreeMap<u32, (usize, usize)> {
+        let mut histogram = BTreeMap::new();
+        for &value in &self.left {
+            histogram.entry(value).or_insert((0, 0)).0 += 1;
+        }
+        for &value in &self.right {
+            histogram.entry(value).or_insert((0, 0)).1 += 1;
+        }
+        histogram
+    }
+
+    // counts are of entries rather than distinct values, so a value appearing twice on
+    // the left and once on the right contributes one to the intersection and one to the
+    // left difference
+    pub fn intersection_count(&self) -> usize {
+        self.histogram().values().map(|&(l, r)| l.min(r)).sum()
+    }
+
+    pub fn left_only_count(&self) -> usize {
+        self.histogram()
+            .values()
+            .map(|&(l, r)| l.saturating_sub(r))
+            .sum()
+    }
+
+    pub fn right_only_count(&self) -> usize {
+        self.histogram()
+            .values()
+            .map(|&(l, r)| r.saturating_sub(l))
+            .sum()
+    }
+
+    // every left value multiplied by the number of times it appears on the right
+    pub fn similarity_score(&self) -> u32 {
+        let right_counts = self.right.iter().counts();
+        self.left
+            .iter()
+            .map(|a| a * *right_counts.get(a).unwrap_or(&0) as u32)
+            .sum()
+    }
+}
+
+fn format_distance(distance: Option<f64>) -> String {
+    distance.map_or("-".to_string(), |d| d.to_string())
+}
+
+impl fmt::Display for ListComparison {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let (left_unpaired, right_unpaired) = self.unpaired();
+        writeln!(
+            f,
+            "pairs: {}, unpaired: {} left, {} right",
+            self.pairs().len(),
+            left_unpaired.len(),
+            right_unpaired.len()
+        )?;
+        writeln!(f, "total distance: {}", self.total_distance())?;
+        writeln!(
+            f,
+            "median distance: {}",
+            format_distance(self.median_distance())
+        )?;
+        writeln!(
+            f,
+            "90th percentile distance: {}",
+            format_distance(self.percentile_distance(90.0))
+        )?;
+        writeln!(f, "similarity score: {}", self.similarity_score())?;
+        writeln!(
+            f,
+            "in both: {}, left only: {}, right only: {}",
+            self.intersection_count(),
+            self.left_only_count(),
+            self.right_only_count()
+        )?;
+        write!(f, "value left right")?;
+        for (value, (left, right)) in self.histogram() {
+            write!(f, "\n{} {} {}", value, left, right)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn example(pairing: Pairing) -> ListComparison {
+        ListComparison::new(vec![3, 4, 2, 1, 3, 3], vec![4, 3, 5, 3, 9, 3], pairing)
+    }
+
+    #[test]
+    fn test_distances() {
+        let comparison = example(Pairing::Strict);
+        assert_eq!(comparison.distances(), vec![2, 1, 0, 1, 2, 5]);
+        assert_eq!(comparison.total_distance(), 11);
+        assert_eq!(comparison.similarity_score(), 31);
+    }
+
+    #[test]
+    fn test_percentile_distance() {
+        let comparison = example(Pairing::Strict);
+        assert_eq!(comparison.median_distance(), Some(1.5));
+        assert_eq!(comparison.percentile_distance(0.0), Some(0.0));
+        assert_eq!(comparison.percentile_distance(100.0), Some(5.0));
+        assert_eq!(comparison.percentile_distance(90.0), Some(3.5));
+        assert_eq!(
+            ListComparison::new(vec![], vec![], Pairing::Strict).median_distance(),
+            None
+        );
+    }
+
+    #[test]
+    fn test_histogram() {
+        let comparison = example(Pairing::Strict);
+        assert_eq!(
+            comparison.histogram().into_iter().collect::<Vec<_>>(),
+            vec![
+                (1, (1, 0)),
+                (2, (1, 0)),
+                (3, (3, 3)),
+                (4, (1, 1)),
+                (5, (0, 1)),
+                (9, (0, 1))
+            ]
+        );
+        assert_eq!(comparison.intersection_count(), 4);
+        assert_eq!(comparison.left_only_count(), 2);
+        assert_eq!(comparison.right_only_count(), 2);
+    }
+
+    #[test]
+    fn test_unequal_lengths() {
+        let truncated = ListComparison::new(vec![5, 1, 3], vec![2, 8], Pairing::Truncate);
+        assert_eq!(truncated.pairs(), vec![(1, 2), (3, 8)]);
+        assert_eq!(truncated.unpaired(), (&[5][..], &[][..]));
+
+        let padded = ListComparison::new(vec![5, 1, 3], vec![2, 8], Pairing::Pad(0));
+        assert_eq!(padded.pairs(), vec![(1, 2), (3, 8), (5, 0)]);
+        assert_eq!(padded.unpaired(), (&[][..], &[][..]));
+        assert_eq!(padded.total_distance(), 11);
+    }
+
+    #[test]
+    #[should_panic(expected = "Lists have different lengths (3 and 2)")]
+    fn test_strict_unequal_lengths() {
+        ListComparison::new(vec![5, 1, 3], vec![2, 8], Pairing::Strict);
+    }
+
+    #[test]
+    fn test_pairing_from_str() {
+        assert_eq!(Pairing::from("strict"), Pairing::Strict);
+        assert_eq!(Pairing::from("truncate"), Pairing::Truncate);
+        assert_eq!(Pairing::from("pad=7"), Pairing::Pad(7));
+    }
+}
@@ -1,51 +1,80 @@
+use advent_of_code_2024::comparison::{ListComparison, Pairing};
 use advent_of_code_2024::to_u32;
-use itertools::Itertools;
 
-fn parse_line(line: &str) -> (u32, u32) {
+// a line with a single number belongs to the right column only if it is indented
+fn parse_line(line: &str) -> (Option<u32>, Option<u32>) {
     let nums = line.split_whitespace().map(to_u32).collect::<Vec<u32>>();
-    (nums[0], nums[1])
+    match nums[..] {
+        [a, b] => (Some(a), Some(b)),
+        [b] if line.starts_with(char::is_whitespace) => (None, Some(b)),
+        [a] => (Some(a), None),
+        _ => panic!("Invalid line: {}", line),
+    }
 }
 
 fn parse_columns(input: &str) -> (Vec<u32>, Vec<u32>) {
-    input.lines().map(parse_line).unzip()
-}
-
-fn distance(a: u32, b: u32) -> u32 {
-    if a > b {
-        a - b
-    } else {
-        b - a
-    }
+    let (left, right): (Vec<Option<u32>>, Vec<Option<u32>>) = input
+        .lines()
+        .filter(|l| !l.trim().is_empty())
+        .map(parse_line)
+        .unzip();
+    (
+        left.into_iter().flatten().collect(),
+        right.into_iter().flatten().collect(),
+    )
 }
 
-fn similarity_score(a: u32, occurances: u32) -> u32 {
-    a * occurances as u32
+pub fn compare(input: &str, pairing: Pairing) -> ListComparison {
+    let (left, right) = parse_columns(input);
+    ListComparison::new(left, right, pairing)
 }
 
 pub fn part_a(input: &str) -> u32 {
-    let (left, right) = parse_columns(input);
-    let sorted_left = left.into_iter().sorted().collect::<Vec<u32>>();
-    let sorted_right = right.into_iter().sorted().collect::<Vec<u32>>();
-    let distances = sorted_left
-        .iter()
-        .zip(sorted_right.iter())
-        .map(|(a, b)| distance(*a, *b));
-    distances.sum()
+    compare(input, Pairing::Strict).total_distance()
 }
 
 pub fn part_b(input: &str) -> u32 {
-    let (left, right) = parse_columns(input);
-    let right_counts = right.into_iter().counts();
-    let similarity_scores = left
-        .into_iter()
-        .map(|a| similarity_score(a, *right_counts.get(&a).unwrap_or(&0) as u32));
-    similarity_scores.sum()
+    compare(input, Pairing::Strict).similarity_score()
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use advent_of_code_2024::read_test_file;
+    use advent_of_code_2024::{read_test_file, trimmed_lines};
+
+    #[test]
+    fn test_parse_columns_unequal_lengths() {
+        let input = "3   4\n4\n    5\n1   3\n";
+        assert_eq!(parse_columns(input), (vec![3, 4, 1], vec![4, 5, 3]));
+    }
+
+    #[test]
+    fn test_compare() {
+        let input = read_test_file(1);
+        let expected = r#"
+            pairs: 6, unpaired: 0 left, 0 right
+            total distance: 11
+            median distance: 1.5
+            90th percentile distance: 3.5
+            similarity score: 31
+            in both: 4, left only: 2, right only: 2
+            value left right
+            1 1 0
+            2 1 0
+            3 3 3
+            4 1 1
+            5 0 1
+            9 0 1
+        "#;
+        let expected_lines = trimmed_lines(expected);
+        assert_eq!(
+            compare(&input, Pairing::Strict)
+                .to_string()
+                .lines()
+                .collect::<Vec<&str>>(),
+            expected_lines
+        );
+    }
 
     #[test]
     fn test_part_a() {
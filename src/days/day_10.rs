@@ -0,0 +1,102 @@
+use advent_of_code_2024::grid::{build_grid, neighbours};
+use std::collections::HashSet;
+
+type Position = (usize, usize);
+
+// anything that isn't a digit is impassable
+fn height(grid: &[Vec<char>], (x, y): Position) -> Option<u32> {
+    grid[y][x].to_digit(10)
+}
+
+// a value for every cell, built from the peaks down: each height 9 cell starts from
+// `peak` and each lower cell merges the values of its neighbours one step higher. every
+// cell is visited once, however many trails pass through it
+fn climb<T: Default + Clone>(
+    grid: &[Vec<char>],
+    peak: impl Fn(Position) -> T,
+    merge: impl Fn(&mut T, &T),
+) -> Vec<Vec<T>> {
+    let mut by_height: Vec<Vec<Position>> = vec![Vec::new(); 10];
+    for y in 0..grid.len() {
+        for x in 0..grid[y].len() {
+            if let Some(h) = height(grid, (x, y)) {
+                by_height[h as usize].push((x, y));
+            }
+        }
+    }
+
+    let mut values = vec![vec![T::default(); grid[0].len()]; grid.len()];
+    for &(x, y) in &by_height[9] {
+        values[y][x] = peak((x, y));
+    }
+    for h in (0..9).rev() {
+        for &(x, y) in &by_height[h] {
+            let mut value = T::default();
+            for (nx, ny) in neighbours(grid, (x, y)) {
+                if height(grid, (nx, ny)) == Some(h as u32 + 1) {
+                    merge(&mut value, &values[ny][nx]);
+                }
+            }
+            values[y][x] = value;
+        }
+    }
+    values
+}
+
+fn trailhead_total<T: Default + Clone>(
+    grid: &[Vec<char>],
+    values: &[Vec<T>],
+    score: impl Fn(&T) -> u32,
+) -> u32 {
+    (0..grid.len())
+        .flat_map(|y| (0..grid[y].len()).map(move |x| (x, y)))
+        .filter(|&p| height(grid, p) == Some(0))
+        .map(|(x, y)| score(&values[y][x]))
+        .sum()
+}
+
+pub fn part_a(input: &str) -> u32 {
+    let grid = build_grid(input);
+    let peaks = climb(
+        &grid,
+        |p| HashSet::from([p]),
+        |reachable: &mut HashSet<Position>, above| reachable.extend(above),
+    );
+    trailhead_total(&grid, &peaks, |reachable| reachable.len() as u32)
+}
+
+pub fn part_b(input: &str) -> u32 {
+    let grid = build_grid(input);
+    let trails = climb(&grid, |_| 1, |count: &mut u32, above| *count += above);
+    trailhead_total(&grid, &trails, |&count| count)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use advent_of_code_2024::read_test_file;
+
+    #[test]
+    fn test_part_a_impassable() {
+        let input = "..90..9\n...1.98\n...2..7\n6543456\n765.987\n876....\n987....";
+        assert_eq!(part_a(input), 4);
+    }
+
+    #[test]
+    fn test_part_b_impassable() {
+        let input = ".....0.\n..4321.\n..5..2.\n..6543.\n..7..4.\n..8765.\n..9....";
+        assert_eq!(part_b(input), 3);
+    }
+
+    #[test]
+    fn test_part_a() {
+        let input = read_test_file(10);
+        assert_eq!(part_a(&input), 36);
+    }
+
+    #[test]
+    fn test_part_b() {
+        let input = read_test_file(10);
+        assert_eq!(part_b(&input), 81);
+    }
+}
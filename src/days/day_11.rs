@@ -0,0 +1,106 @@
+use advent_of_code_2024::to_u64;
+use itertools::Itertools;
+use std::collections::HashMap;
+
+fn parse_stones(input: &str) -> HashMap<u64, u64> {
+    input
+        .split_whitespace()
+        .map(to_u64)
+        .counts()
+        .into_iter()
+        .map(|(stone, count)| (stone, count as u64))
+        .collect()
+}
+
+// the stones replacing `stone`, or None if its new value overflows
+fn blink_stone(stone: u64) -> Option<Vec<u64>> {
+    if stone == 0 {
+        return Some(vec![1]);
+    }
+    let digits = stone.ilog10() + 1;
+    if digits.is_multiple_of(2) {
+        let half = 10u64.pow(digits / 2);
+        Some(vec![stone / half, stone % half])
+    } else {
+        stone.checked_mul(2024).map(|value| vec![value])
+    }
+}
+
+// stones never interact and their order is irrelevant to the total, so equal stones are
+// tracked as one entry with a count
+fn blink(stones: &HashMap<u64, u64>, blink_number: u32) -> HashMap<u64, u64> {
+    let mut next = HashMap::new();
+    for (&stone, &count) in stones {
+        let new_stones = blink_stone(stone)
+            .unwrap_or_else(|| panic!("Stone value overflows after {} blinks", blink_number));
+        for new_stone in new_stones {
+            let total: &mut u64 = next.entry(new_stone).or_default();
+            *total = total
+                .checked_add(count)
+                .unwrap_or_else(|| panic!("Stone count overflows after {} blinks", blink_number));
+        }
+    }
+    next
+}
+
+pub fn stone_count(input: &str, blinks: u32) -> u64 {
+    let stones = (1..=blinks).fold(parse_stones(input), |stones, n| blink(&stones, n));
+    stones
+        .values()
+        .try_fold(0u64, |total, &count| total.checked_add(count))
+        .unwrap_or_else(|| panic!("Stone count overflows after {} blinks", blinks))
+}
+
+pub fn part_a(input: &str) -> u64 {
+    stone_count(input, 25)
+}
+
+pub fn part_b(input: &str) -> u64 {
+    stone_count(input, 75)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use advent_of_code_2024::read_test_file;
+
+    #[test]
+    fn test_blink_stone() {
+        assert_eq!(blink_stone(0), Some(vec![1]));
+        assert_eq!(blink_stone(1000), Some(vec![10, 0]));
+        assert_eq!(blink_stone(999), Some(vec![2021976]));
+        assert_eq!(blink_stone(1_000_000_000_000_000_000), None);
+    }
+
+    #[test]
+    fn test_stone_count() {
+        assert_eq!(stone_count("0 1 10 99 999", 1), 7);
+        let input = read_test_file(11);
+        assert_eq!(stone_count(&input, 0), 2);
+        assert_eq!(stone_count(&input, 6), 22);
+    }
+
+    #[test]
+    #[should_panic(expected = "Stone count overflows after")]
+    fn test_stone_count_overflow() {
+        stone_count("125 17", 150);
+    }
+
+    #[test]
+    #[should_panic(expected = "Stone value overflows after 1 blinks")]
+    fn test_stone_value_overflow() {
+        stone_count("1000000000000000000", 1);
+    }
+
+    #[test]
+    fn test_part_a() {
+        let input = read_test_file(11);
+        assert_eq!(part_a(&input), 55312);
+    }
+
+    #[test]
+    fn test_part_b() {
+        let input = read_test_file(11);
+        assert_eq!(part_b(&input), 65601038650482);
+    }
+}
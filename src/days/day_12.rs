@@ -0,0 +1,110 @@
+use advent_of_code_2024::grid::build_grid;
+use advent_of_code_2024::regions::Regions;
+
+// a quarter of the neighbourhood around a cell: the two orthogonal neighbours and the
+// diagonal between them
+static QUADRANTS: [(i64, i64); 4] = [(1, -1), (1, 1), (-1, 1), (-1, -1)];
+
+struct Measurements {
+    area: u32,
+    perimeter: u32,
+    sides: u32,
+}
+
+fn same_region(regions: &Regions, label: usize, (x, y): (i64, i64)) -> bool {
+    regions.region_at((x, y)) == Some(label)
+}
+
+// every side of a region starts and ends at a corner, so counting corners counts sides.
+// a cell has an outer corner in a quadrant when neither orthogonal neighbour is in its
+// region, and an inner corner when both are but the diagonal isn't
+fn corners(regions: &Regions, label: usize, (x, y): (i64, i64)) -> u32 {
+    QUADRANTS
+        .iter()
+        .filter(|(dx, dy)| {
+            let horizontal = same_region(regions, label, (x + dx, y));
+            let vertical = same_region(regions, label, (x, y + dy));
+            let diagonal = same_region(regions, label, (x + dx, y + dy));
+            (!horizontal && !vertical) || (horizontal && vertical && !diagonal)
+        })
+        .count() as u32
+}
+
+fn measure(regions: &Regions) -> Vec<Measurements> {
+    regions
+        .cells()
+        .iter()
+        .enumerate()
+        .map(|(label, cells)| {
+            let mut measurements = Measurements {
+                area: cells.len() as u32,
+                perimeter: 0,
+                sides: 0,
+            };
+            for &(x, y) in cells {
+                let (x, y) = (x as i64, y as i64);
+                let joined = [(x, y - 1), (x + 1, y), (x, y + 1), (x - 1, y)]
+                    .into_iter()
+                    .filter(|&p| same_region(regions, label, p))
+                    .count() as u32;
+                measurements.perimeter += 4 - joined;
+                measurements.sides += corners(regions, label, (x, y));
+            }
+            measurements
+        })
+        .collect()
+}
+
+fn fence_price(input: &str, price: fn(&Measurements) -> u32) -> u32 {
+    let grid = build_grid(input);
+    let regions = Regions::label(&grid, |a, b| a == b);
+    measure(&regions).iter().map(price).sum()
+}
+
+pub fn part_a(input: &str) -> u32 {
+    fence_price(input, |m| m.area * m.perimeter)
+}
+
+pub fn part_b(input: &str) -> u32 {
+    fence_price(input, |m| m.area * m.sides)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use advent_of_code_2024::read_test_file;
+
+    #[test]
+    fn test_measure() {
+        let grid = build_grid("AAAA\nBBCD\nBBCC\nEEEC");
+        let regions = Regions::label(&grid, |a, b| a == b);
+        let measured = measure(&regions)
+            .iter()
+            .map(|m| (m.area, m.perimeter, m.sides))
+            .collect::<Vec<(u32, u32, u32)>>();
+        assert_eq!(
+            measured,
+            vec![(4, 10, 4), (4, 8, 4), (4, 10, 8), (1, 4, 4), (3, 8, 4)]
+        );
+    }
+
+    #[test]
+    fn test_part_b_enclosed_regions() {
+        let input = "AAAAAA\nAAABBA\nAAABBA\nABBAAA\nABBAAA\nAAAAAA";
+        assert_eq!(part_b(input), 368);
+        let input = "EEEEE\nEXXXX\nEEEEE\nEXXXX\nEEEEE";
+        assert_eq!(part_b(input), 236);
+    }
+
+    #[test]
+    fn test_part_a() {
+        let input = read_test_file(12);
+        assert_eq!(part_a(&input), 1930);
+    }
+
+    #[test]
+    fn test_part_b() {
+        let input = read_test_file(12);
+        assert_eq!(part_b(&input), 1206);
+    }
+}
@@ -0,0 +1,229 @@
+use advent_of_code_2024::to_i64;
+
+const A_COST: i128 = 3;
+const B_COST: i128 = 1;
+const PRIZE_OFFSET: i64 = 10_000_000_000_000;
+
+#[derive(PartialEq, Debug)]
+struct Machine {
+    a: (i64, i64),
+    b: (i64, i64),
+    prize: (i64, i64),
+}
+
+// the values of a line like `Button A: X+94, Y+34` or `Prize: X=8400, Y=5400`. button
+// offsets are always positive
+fn parse_values(line: &str, separator: &str) -> (i64, i64) {
+    let values = line
+        .split_once(": ")
+        .unwrap_or_else(|| panic!("Invalid machine line: {}", line))
+        .1
+        .split(", ")
+        .map(|value| {
+            let digits = value[1..]
+                .strip_prefix(separator)
+                .unwrap_or_else(|| panic!("Invalid machine value: {}", value));
+            to_i64(digits)
+        })
+        .collect::<Vec<i64>>();
+    (values[0], values[1])
+}
+
+impl From<&str> for Machine {
+    fn from(s: &str) -> Self {
+        let lines = s.lines().collect::<Vec<&str>>();
+        Machine {
+            a: parse_values(lines[0], "+"),
+            b: parse_values(lines[1], "+"),
+            prize: parse_values(lines[2], "="),
+        }
+    }
+}
+
+fn machines(input: &str) -> Vec<Machine> {
+    input
+        .split("\n\n")
+        .filter(|block| !block.trim().is_empty())
+        .map(Machine::from)
+        .collect()
+}
+
+fn cost((a, b): (i128, i128)) -> i128 {
+    A_COST * a + B_COST * b
+}
+
+// returns (g, x, y) with a * x + b * y = g = gcd(a, b)
+fn extended_gcd(a: i128, b: i128) -> (i128, i128, i128) {
+    if b == 0 {
+        (a, 1, 0)
+    } else {
+        let (g, x, y) = extended_gcd(b, a % b);
+        (g, y, x - (a / b) * y)
+    }
+}
+
+fn ceil_div(a: i128, b: i128) -> i128 {
+    -(-a).div_euclid(b)
+}
+
+// the cheapest non-negative a, b with a * u + b * v = w, for u and v that aren't negative.
+//
+// solutions are a = a0 + k * v / g and b = b0 - k * u / g, so the cost is linear in k and
+// the cheapest is at whichever end of the valid range of k the slope points to
+fn cheapest_on_line(u: i128, v: i128, w: i128, max: Option<i128>) -> Option<(i128, i128)> {
+    let within = |presses: i128| presses >= 0 && max.is_none_or(|m| presses <= m);
+    match (u, v) {
+        (0, 0) => (w == 0).then_some((0, 0)),
+        (0, v) => (w % v == 0 && within(w / v)).then_some((0, w / v)),
+        (u, 0) => (w % u == 0 && within(w / u)).then_some((w / u, 0)),
+        (u, v) => {
+            let (g, x, y) = extended_gcd(u, v);
+            if w % g != 0 {
+                return None;
+            }
+            let (a0, b0) = (x * (w / g), y * (w / g));
+            let (step_a, step_b) = (v / g, u / g);
+
+            let mut low = ceil_div(-a0, step_a);
+            let mut high = b0.div_euclid(step_b);
+            if let Some(m) = max {
+                low = low.max(ceil_div(b0 - m, step_b));
+                high = high.min((m - a0).div_euclid(step_a));
+            }
+            if low > high {
+                return None;
+            }
+            let k = if A_COST * step_a > B_COST * step_b {
+                low
+            } else {
+                high
+            };
+            Some((a0 + k * step_a, b0 - k * step_b))
+        }
+    }
+}
+
+// solves a * A + b * B = prize by Cramer's rule, in i128 so the offset prizes of part b
+// can't overflow the cross products. only whole, non-negative press counts are valid
+fn cheapest_presses(machine: &Machine, max: Option<i64>) -> Option<(i128, i128)> {
+    let (ax, ay) = (machine.a.0 as i128, machine.a.1 as i128);
+    let (bx, by) = (machine.b.0 as i128, machine.b.1 as i128);
+    let (px, py) = (machine.prize.0 as i128, machine.prize.1 as i128);
+    let max = max.map(|m| m as i128);
+
+    let determinant = ax * by - ay * bx;
+    if determinant == 0 {
+        // both buttons move along the same line, which must also pass through the prize
+        if ax * py != ay * px || bx * py != by * px {
+            return None;
+        }
+        return if (ax, bx) != (0, 0) {
+            cheapest_on_line(ax, bx, px, max)
+        } else {
+            cheapest_on_line(ay, by, py, max)
+        };
+    }
+
+    let a_numerator = px * by - py * bx;
+    let b_numerator = ax * py - ay * px;
+    if a_numerator % determinant != 0 || b_numerator % determinant != 0 {
+        return None;
+    }
+    let presses = (a_numerator / determinant, b_numerator / determinant);
+    let within = |p: i128| p >= 0 && max.is_none_or(|m| p <= m);
+    (within(presses.0) && within(presses.1)).then_some(presses)
+}
+
+fn total_tokens(machines: &[Machine], max: Option<i64>) -> i64 {
+    machines
+        .iter()
+        .filter_map(|machine| cheapest_presses(machine, max))
+        .map(cost)
+        .sum::<i128>() as i64
+}
+
+pub fn part_a(input: &str) -> i64 {
+    total_tokens(&machines(input), Some(100))
+}
+
+pub fn part_b(input: &str) -> i64 {
+    let machines = machines(input)
+        .into_iter()
+        .map(|machine| Machine {
+            prize: (
+                machine.prize.0 + PRIZE_OFFSET,
+                machine.prize.1 + PRIZE_OFFSET,
+            ),
+            ..machine
+        })
+        .collect::<Vec<Machine>>();
+    total_tokens(&machines, None)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use advent_of_code_2024::read_test_file;
+
+    #[test]
+    fn test_machines() {
+        let input = read_test_file(13);
+        assert_eq!(
+            machines(&input)[0],
+            Machine {
+                a: (94, 34),
+                b: (22, 67),
+                prize: (8400, 5400),
+            }
+        );
+    }
+
+    #[test]
+    fn test_cheapest_presses() {
+        let machines = machines(&read_test_file(13));
+        assert_eq!(cheapest_presses(&machines[0], Some(100)), Some((80, 40)));
+        assert_eq!(cheapest_presses(&machines[1], Some(100)), None);
+        assert_eq!(cheapest_presses(&machines[2], Some(100)), Some((38, 86)));
+    }
+
+    #[test]
+    fn test_cheapest_presses_collinear_buttons() {
+        let machine = |a, b, prize| Machine { a, b, prize };
+        assert_eq!(
+            cheapest_presses(&machine((1, 1), (3, 3), (6, 6)), None),
+            Some((0, 2))
+        );
+        assert_eq!(
+            cheapest_presses(&machine((4, 4), (1, 1), (8, 8)), None),
+            Some((2, 0))
+        );
+        assert_eq!(
+            cheapest_presses(&machine((4, 4), (1, 1), (8, 8)), Some(1)),
+            None
+        );
+        assert_eq!(
+            cheapest_presses(&machine((2, 4), (3, 6), (7, 14)), None),
+            Some((2, 1))
+        );
+        assert_eq!(
+            cheapest_presses(&machine((2, 4), (4, 8), (7, 14)), None),
+            None
+        );
+        assert_eq!(
+            cheapest_presses(&machine((1, 1), (3, 3), (6, 7)), None),
+            None
+        );
+    }
+
+    #[test]
+    fn test_part_a() {
+        let input = read_test_file(13);
+        assert_eq!(part_a(&input), 480);
+    }
+
+    #[test]
+    fn test_part_b() {
+        let input = read_test_file(13);
+        assert_eq!(part_b(&input), 875318608908);
+    }
+}
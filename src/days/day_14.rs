@@ -0,0 +1,255 @@
+use advent_of_code_2024::{lcm, to_i64};
+use std::fmt;
+
+type Position = (i64, i64);
+
+const SAFETY_STEPS: i64 = 100;
+
+#[derive(PartialEq, Clone, Copy, Debug)]
+pub struct Board {
+    width: i64,
+    height: i64,
+}
+
+static PUZZLE_BOARD: Board = Board {
+    width: 101,
+    height: 103,
+};
+
+// a board written as `<width>x<height>`, e.g. `11x7` for the example
+impl From<&str> for Board {
+    fn from(s: &str) -> Self {
+        let (width, height) = s
+            .split_once('x')
+            .unwrap_or_else(|| panic!("Invalid board size: {}", s));
+        Board {
+            width: to_i64(width),
+            height: to_i64(height),
+        }
+    }
+}
+
+#[derive(PartialEq, Debug)]
+struct Robot {
+    position: Position,
+    velocity: Position,
+}
+
+fn parse_pair(s: &str) -> Position {
+    let (x, y) = s[2..]
+        .split_once(',')
+        .unwrap_or_else(|| panic!("Invalid robot value: {}", s));
+    (to_i64(x), to_i64(y))
+}
+
+impl From<&str> for Robot {
+    fn from(s: &str) -> Self {
+        let (position, velocity) = s
+            .split_once(' ')
+            .unwrap_or_else(|| panic!("Invalid robot: {}", s));
+        Robot {
+            position: parse_pair(position),
+            velocity: parse_pair(velocity),
+        }
+    }
+}
+
+fn parse_robots(input: &str) -> Vec<Robot> {
+    input.lines().map(Robot::from).collect()
+}
+
+// robots pass through each other, so each one's position after any number of steps is
+// independent of the rest
+fn positions_after(robots: &[Robot], board: Board, steps: i64) -> Vec<Position> {
+    robots
+        .iter()
+        .map(|robot| {
+            (
+                (robot.position.0 + robot.velocity.0 * steps).rem_euclid(board.width),
+                (robot.position.1 + robot.velocity.1 * steps).rem_euclid(board.height),
+            )
+        })
+        .collect()
+}
+
+// robots on the middle row or column of an odd sized board are in no quadrant
+fn safety_factor(positions: &[Position], board: Board) -> u64 {
+    let mut quadrants = [0; 4];
+    for &(x, y) in positions {
+        let left = x < board.width / 2;
+        let right = x >= (board.width + 1) / 2;
+        let top = y < board.height / 2;
+        let bottom = y >= (board.height + 1) / 2;
+        match (left, right, top, bottom) {
+            (true, _, true, _) => quadrants[0] += 1,
+            (_, true, true, _) => quadrants[1] += 1,
+            (true, _, _, true) => quadrants[2] += 1,
+            (_, true, _, true) => quadrants[3] += 1,
+            _ => {}
+        }
+    }
+    quadrants.iter().product()
+}
+
+fn variance(values: impl Iterator<Item = i64> + Clone) -> f64 {
+    let count = values.clone().count() as f64;
+    let mean = values.clone().sum::<i64>() as f64 / count;
+    values.map(|v| (v as f64 - mean).powi(2)).sum::<f64>() / count
+}
+
+// the robots are scattered almost uniformly except when they draw the picture, when most
+// of them cluster together. positions repeat after lcm(width, height) steps, so the
+// picture is the step in that period where the positions spread out the least
+fn find_picture(robots: &[Robot], board: Board) -> i64 {
+    let period = lcm(board.width, board.height);
+    (0..period)
+        .map(|steps| {
+            let positions = positions_after(robots, board, steps);
+            let spread =
+                variance(positions.iter().map(|p| p.0)) + variance(positions.iter().map(|p| p.1));
+            (steps, spread)
+        })
+        .min_by(|a, b| a.1.total_cmp(&b.1))
+        .map_or(0, |(steps, _)| steps)
+}
+
+// a frame of the board, with the number of robots on each cell
+struct Frame {
+    board: Board,
+    positions: Vec<Position>,
+}
+
+impl fmt::Display for Frame {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let mut counts = vec![vec![0; self.board.width as usize]; self.board.height as usize];
+        for &(x, y) in &self.positions {
+            counts[y as usize][x as usize] += 1;
+        }
+        let rows = counts.iter().map(|row| {
+            row.iter()
+                .map(|&count| match count {
+                    0 => '.',
+                    1..=9 => char::from_digit(count, 10).unwrap(),
+                    _ => '+',
+                })
+                .collect::<String>()
+        });
+        write!(f, "{}", rows.collect::<Vec<String>>().join("\n"))
+    }
+}
+
+pub fn simulate(input: &str, board: Board, steps: i64) -> String {
+    let positions = positions_after(&parse_robots(input), board, steps);
+    format!(
+        "After {} seconds, safety factor {}:\n{}",
+        steps,
+        safety_factor(&positions, board),
+        Frame { board, positions }
+    )
+}
+
+// part a renders the board the safety factor is taken from, part b the picture
+pub fn render(input: &str, part: char, board: Board) -> String {
+    let steps = match part {
+        'b' => find_picture(&parse_robots(input), board),
+        _ => SAFETY_STEPS,
+    };
+    simulate(input, board, steps)
+}
+
+pub fn part_a(input: &str) -> u64 {
+    let positions = positions_after(&parse_robots(input), PUZZLE_BOARD, SAFETY_STEPS);
+    safety_factor(&positions, PUZZLE_BOARD)
+}
+
+pub fn part_b(input: &str) -> i64 {
+    find_picture(&parse_robots(input), PUZZLE_BOARD)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use advent_of_code_2024::{read_test_file, trimmed_lines};
+
+    static EXAMPLE_BOARD: Board = Board {
+        width: 11,
+        height: 7,
+    };
+
+    #[test]
+    fn test_parse_robots() {
+        let robots = parse_robots(&read_test_file(14));
+        assert_eq!(
+            robots[0],
+            Robot {
+                position: (0, 4),
+                velocity: (3, -3)
+            }
+        );
+        assert_eq!(Board::from("11x7"), EXAMPLE_BOARD);
+    }
+
+    #[test]
+    fn test_positions_after() {
+        let robot = Robot::from("p=2,4 v=2,-3");
+        let path = (0..=5)
+            .map(|steps| positions_after(std::slice::from_ref(&robot), EXAMPLE_BOARD, steps)[0])
+            .collect::<Vec<Position>>();
+        assert_eq!(path, vec![(2, 4), (4, 1), (6, 5), (8, 2), (10, 6), (1, 3)]);
+    }
+
+    #[test]
+    fn test_frame() {
+        let input = read_test_file(14);
+        let positions = positions_after(&parse_robots(&input), EXAMPLE_BOARD, 100);
+        let expected = r#"
+            ......2..1.
+            ...........
+            1..........
+            .11........
+            .....1.....
+            ...12......
+            .1....1....
+        "#;
+        let expected_lines = trimmed_lines(expected);
+        let frame = Frame {
+            board: EXAMPLE_BOARD,
+            positions,
+        };
+        assert_eq!(
+            frame.to_string().lines().collect::<Vec<&str>>(),
+            expected_lines
+        );
+    }
+
+    #[test]
+    fn test_find_picture() {
+        // robots scattered everywhere except at step 40, when they all sit in a 3x3 block
+        let mut seed: i64 = 17;
+        let mut next = |limit: i64| {
+            seed = (seed * 1103515245 + 12345) % 2147483648;
+            seed % limit
+        };
+        let robots = (0..200)
+            .map(|_| {
+                let velocity = (next(21) - 10, next(21) - 10);
+                let target = (next(3) + 50, next(3) + 50);
+                Robot {
+                    position: (
+                        (target.0 - velocity.0 * 40).rem_euclid(101),
+                        (target.1 - velocity.1 * 40).rem_euclid(103),
+                    ),
+                    velocity,
+                }
+            })
+            .collect::<Vec<Robot>>();
+        assert_eq!(find_picture(&robots, PUZZLE_BOARD), 40);
+    }
+
+    #[test]
+    fn test_safety_factor() {
+        let input = read_test_file(14);
+        let positions = positions_after(&parse_robots(&input), EXAMPLE_BOARD, 100);
+        assert_eq!(safety_factor(&positions, EXAMPLE_BOARD), 12);
+    }
+}
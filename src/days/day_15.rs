@@ -0,0 +1,206 @@
+use advent_of_code_2024::grid::{build_grid, Direction, DIRECTIONS};
+use std::collections::HashSet;
+use std::fmt;
+
+struct Warehouse {
+    grid: Vec<Vec<char>>,
+    robot: (i64, i64),
+}
+
+impl From<&str> for Warehouse {
+    fn from(s: &str) -> Self {
+        let grid = build_grid(s);
+        let robot = grid
+            .iter()
+            .enumerate()
+            .find_map(|(y, row)| {
+                row.iter()
+                    .position(|&c| c == '@')
+                    .map(|x| (x as i64, y as i64))
+            })
+            .expect("Warehouse has no robot");
+        Warehouse { grid, robot }
+    }
+}
+
+impl fmt::Display for Warehouse {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let rows = self.grid.iter().map(|row| row.iter().collect::<String>());
+        write!(f, "{}", rows.collect::<Vec<String>>().join("\n"))
+    }
+}
+
+fn map_and_moves(input: &str) -> (&str, Vec<&'static Direction>) {
+    let (map, moves) = input
+        .split_once("\n\n")
+        .expect("Input needs a map and moves separated by a blank line");
+    let moves = moves
+        .chars()
+        .filter(|c| !c.is_whitespace())
+        .map(|c| {
+            DIRECTIONS
+                .iter()
+                .find(|m| m.symbol == c)
+                .unwrap_or_else(|| panic!("Invalid move: {}", c))
+        })
+        .collect();
+    (map, moves)
+}
+
+// every tile of the map becomes two, so boxes become `[]`
+fn widen(map: &str) -> String {
+    map.chars()
+        .map(|c| match c {
+            '#' => "##".to_string(),
+            'O' => "[]".to_string(),
+            '.' => "..".to_string(),
+            '@' => "@.".to_string(),
+            c => c.to_string(),
+        })
+        .collect()
+}
+
+impl Warehouse {
+    fn cell(&self, (x, y): (i64, i64)) -> char {
+        self.grid[y as usize][x as usize]
+    }
+
+    // the cells that move along with the robot, nearest first, or None if the push is
+    // blocked by a wall. a wide box pushed vertically also pushes whatever is in front of
+    // its other half, so the pushed cells can fan out into a tree
+    fn pushed_cells(&self, m: &Direction) -> Option<Vec<(i64, i64)>> {
+        let mut pushed = vec![self.robot];
+        let mut seen = HashSet::from([self.robot]);
+        let mut i = 0;
+        while i < pushed.len() {
+            let (x, y) = pushed[i];
+            let next = (x + m.x, y + m.y);
+            let mut ahead = vec![next];
+            match self.cell(next) {
+                '#' => return None,
+                '[' if m.y != 0 => ahead.push((next.0 + 1, next.1)),
+                ']' if m.y != 0 => ahead.push((next.0 - 1, next.1)),
+                'O' | '[' | ']' => {}
+                _ => ahead.clear(),
+            }
+            for cell in ahead {
+                if seen.insert(cell) {
+                    pushed.push(cell);
+                }
+            }
+            i += 1;
+        }
+        Some(pushed)
+    }
+
+    fn step(&mut self, m: &Direction) {
+        let Some(pushed) = self.pushed_cells(m) else {
+            return;
+        };
+        // cells are found in order of distance from the robot, so moving them furthest
+        // first never overwrites a cell that has yet to move
+        for &(x, y) in pushed.iter().rev() {
+            let (nx, ny) = (x + m.x, y + m.y);
+            self.grid[ny as usize][nx as usize] = self.grid[y as usize][x as usize];
+            self.grid[y as usize][x as usize] = '.';
+        }
+        self.robot = (self.robot.0 + m.x, self.robot.1 + m.y);
+    }
+
+    fn gps_sum(&self) -> u32 {
+        let mut sum = 0;
+        for (y, row) in self.grid.iter().enumerate() {
+            for (x, &c) in row.iter().enumerate() {
+                if c == 'O' || c == '[' {
+                    sum += 100 * y as u32 + x as u32;
+                }
+            }
+        }
+        sum
+    }
+}
+
+fn final_warehouse(input: &str, part: char) -> Warehouse {
+    let (map, moves) = map_and_moves(input);
+    let mut warehouse = match part {
+        'b' => Warehouse::from(widen(map).as_str()),
+        _ => Warehouse::from(map),
+    };
+    for m in moves {
+        warehouse.step(m);
+    }
+    warehouse
+}
+
+pub fn render(input: &str, part: char) -> String {
+    final_warehouse(input, part).to_string()
+}
+
+pub fn part_a(input: &str) -> u32 {
+    final_warehouse(input, 'a').gps_sum()
+}
+
+pub fn part_b(input: &str) -> u32 {
+    final_warehouse(input, 'b').gps_sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use advent_of_code_2024::{read_test_file, trimmed_lines};
+
+    #[test]
+    fn test_render_part_a() {
+        let input = "########\n#..O.O.#\n##@.O..#\n#...O..#\n#.#.O..#\n#...O..#\n#......#\n########\n\n<^^>>>vv<v>>v<<";
+        let expected = r#"
+            ########
+            #....OO#
+            ##.....#
+            #.....O#
+            #.#O@..#
+            #...O..#
+            #...O..#
+            ########
+        "#;
+        assert_eq!(trimmed_lines(&render(input, 'a')), trimmed_lines(expected));
+        assert_eq!(part_a(input), 2028);
+    }
+
+    #[test]
+    fn test_render_part_b() {
+        let input = "#######\n#...#.#\n#.....#\n#..OO@#\n#..O..#\n#.....#\n#######\n\n<vv<<^^<<^^";
+        let expected = r#"
+            ##############
+            ##...[].##..##
+            ##...@.[]...##
+            ##....[]....##
+            ##..........##
+            ##..........##
+            ##############
+        "#;
+        assert_eq!(trimmed_lines(&render(input, 'b')), trimmed_lines(expected));
+        assert_eq!(part_b(input), 618);
+    }
+
+    #[test]
+    fn test_blocked_tree_push() {
+        let input = "########\n#......#\n#..#...#\n#..[]..#\n#...[].#\n#...@..#\n########\n\n^";
+        let (map, moves) = map_and_moves(input);
+        let mut warehouse = Warehouse::from(map);
+        warehouse.step(moves[0]);
+        assert_eq!(warehouse.robot, (4, 5));
+        assert_eq!(warehouse.to_string(), map);
+    }
+
+    #[test]
+    fn test_part_a() {
+        let input = read_test_file(15);
+        assert_eq!(part_a(&input), 10092);
+    }
+
+    #[test]
+    fn test_part_b() {
+        let input = read_test_file(15);
+        assert_eq!(part_b(&input), 9021);
+    }
+}
@@ -0,0 +1,202 @@
+use advent_of_code_2024::grid::{build_grid, state_index, State, TurnPolicy, DIRECTIONS};
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
+
+const STEP_COST: u32 = 1;
+const TURN_COST: u32 = 1000;
+
+// the reindeer starts facing east
+const START_DIRECTION: usize = 1;
+
+fn find(grid: &[Vec<char>], symbol: char) -> (i64, i64) {
+    grid.iter()
+        .enumerate()
+        .find_map(|(y, row)| {
+            row.iter()
+                .position(|&c| c == symbol)
+                .map(|x| (x as i64, y as i64))
+        })
+        .unwrap_or_else(|| panic!("Maze has no {}", symbol))
+}
+
+fn state_at(grid: &[Vec<char>], index: usize) -> State {
+    let cell = index / DIRECTIONS.len();
+    State {
+        position: ((cell % grid[0].len()) as i64, (cell / grid[0].len()) as i64),
+        direction: index % DIRECTIONS.len(),
+    }
+}
+
+// the states one move away with the cost of the move. walking backwards gives the states
+// a move leads from, which is how the distances to the end are found
+fn moves(grid: &[Vec<char>], state: &State, backwards: bool) -> Vec<(State, u32)> {
+    let direction = &DIRECTIONS[state.direction];
+    let sign = if backwards { -1 } else { 1 };
+    let position = (
+        state.position.0 + sign * direction.x,
+        state.position.1 + sign * direction.y,
+    );
+
+    let mut moves = vec![
+        (
+            State {
+                direction: TurnPolicy::Right.turn(state.direction),
+                ..*state
+            },
+            TURN_COST,
+        ),
+        (
+            State {
+                direction: TurnPolicy::Left.turn(state.direction),
+                ..*state
+            },
+            TURN_COST,
+        ),
+    ];
+    // the maze is walled in, so a step never leaves the grid
+    if grid[position.1 as usize][position.0 as usize] != '#' {
+        moves.push((
+            State {
+                position,
+                direction: state.direction,
+            },
+            STEP_COST,
+        ));
+    }
+    moves
+}
+
+// the lowest score from any of the starts to every state, or u32::MAX if unreachable
+fn dijkstra(grid: &[Vec<char>], starts: &[State], backwards: bool) -> Vec<u32> {
+    let mut scores = vec![u32::MAX; grid.len() * grid[0].len() * DIRECTIONS.len()];
+    let mut queue = BinaryHeap::new();
+    for start in starts {
+        let index = state_index(grid, start);
+        scores[index] = 0;
+        queue.push(Reverse((0, index)));
+    }
+
+    while let Some(Reverse((score, index))) = queue.pop() {
+        if score > scores[index] {
+            continue;
+        }
+        for (next, cost) in moves(grid, &state_at(grid, index), backwards) {
+            let next_index = state_index(grid, &next);
+            if score + cost < scores[next_index] {
+                scores[next_index] = score + cost;
+                queue.push(Reverse((score + cost, next_index)));
+            }
+        }
+    }
+    scores
+}
+
+fn end_states(grid: &[Vec<char>]) -> Vec<State> {
+    let position = find(grid, 'E');
+    (0..DIRECTIONS.len())
+        .map(|direction| State {
+            position,
+            direction,
+        })
+        .collect()
+}
+
+fn start_state(grid: &[Vec<char>]) -> State {
+    State {
+        position: find(grid, 'S'),
+        direction: START_DIRECTION,
+    }
+}
+
+fn lowest_score(grid: &[Vec<char>], from_start: &[u32]) -> u32 {
+    end_states(grid)
+        .iter()
+        .map(|end| from_start[state_index(grid, end)])
+        .min()
+        .unwrap()
+}
+
+pub fn part_a(input: &str) -> u32 {
+    let grid = build_grid(input);
+    let from_start = dijkstra(&grid, &[start_state(&grid)], false);
+    lowest_score(&grid, &from_start)
+}
+
+// a state is on a best path when the best score to reach it plus the best score from it
+// to the end is the best score overall
+pub fn part_b(input: &str) -> u32 {
+    let grid = build_grid(input);
+    let from_start = dijkstra(&grid, &[start_state(&grid)], false);
+    let to_end = dijkstra(&grid, &end_states(&grid), true);
+    let best = lowest_score(&grid, &from_start);
+
+    let on_best_path = |index: usize| {
+        from_start[index] != u32::MAX
+            && to_end[index] != u32::MAX
+            && from_start[index] + to_end[index] == best
+    };
+    (0..grid.len() * grid[0].len())
+        .filter(|cell| {
+            (0..DIRECTIONS.len()).any(|direction| on_best_path(cell * DIRECTIONS.len() + direction))
+        })
+        .count() as u32
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use advent_of_code_2024::read_test_file;
+
+    static SECOND_EXAMPLE: &str = "#################
+#...#...#...#..E#
+#.#.#.#.#.#.#.#.#
+#.#.#.#...#...#.#
+#.#.#.#.###.#.#.#
+#...#.#.#.....#.#
+#.#.#.#.#.#####.#
+#.#...#.#.#.....#
+#.#.#####.#.###.#
+#.#.#.......#...#
+#.#.###.#####.###
+#.#.#...#.....#.#
+#.#.#.#####.###.#
+#.#.#.........#.#
+#.#.#.#########.#
+#S#.............#
+#################";
+
+    #[test]
+    fn test_moves() {
+        let grid = build_grid(&read_test_file(16));
+        let start = start_state(&grid);
+        assert_eq!(start.position, (1, 13));
+        let forward = moves(&grid, &start, false);
+        assert_eq!(forward.len(), 3);
+        assert_eq!(
+            forward[2],
+            (
+                State {
+                    position: (2, 13),
+                    ..start
+                },
+                1
+            )
+        );
+        let backward = moves(&grid, &start, true);
+        assert_eq!(backward.len(), 2);
+    }
+
+    #[test]
+    fn test_part_a() {
+        let input = read_test_file(16);
+        assert_eq!(part_a(&input), 7036);
+        assert_eq!(part_a(SECOND_EXAMPLE), 11048);
+    }
+
+    #[test]
+    fn test_part_b() {
+        let input = read_test_file(16);
+        assert_eq!(part_b(&input), 45);
+        assert_eq!(part_b(SECOND_EXAMPLE), 64);
+    }
+}
@@ -0,0 +1,350 @@
+use advent_of_code_2024::to_u64;
+use itertools::Itertools;
+
+// guards against programs that jump back forever, e.g. while searching values of A
+const MAX_INSTRUCTIONS: usize = 1_000_000;
+
+#[derive(PartialEq, Clone, Copy, Debug)]
+enum Opcode {
+    Adv,
+    Bxl,
+    Bst,
+    Jnz,
+    Bxc,
+    Out,
+    Bdv,
+    Cdv,
+}
+
+impl From<u8> for Opcode {
+    fn from(value: u8) -> Self {
+        match value {
+            0 => Opcode::Adv,
+            1 => Opcode::Bxl,
+            2 => Opcode::Bst,
+            3 => Opcode::Jnz,
+            4 => Opcode::Bxc,
+            5 => Opcode::Out,
+            6 => Opcode::Bdv,
+            7 => Opcode::Cdv,
+            _ => panic!("Invalid opcode: {}", value),
+        }
+    }
+}
+
+impl Opcode {
+    fn mnemonic(&self) -> &'static str {
+        match self {
+            Opcode::Adv => "adv",
+            Opcode::Bxl => "bxl",
+            Opcode::Bst => "bst",
+            Opcode::Jnz => "jnz",
+            Opcode::Bxc => "bxc",
+            Opcode::Out => "out",
+            Opcode::Bdv => "bdv",
+            Opcode::Cdv => "cdv",
+        }
+    }
+}
+
+#[derive(PartialEq, Clone, Debug)]
+struct Computer {
+    a: u64,
+    b: u64,
+    c: u64,
+    program: Vec<u8>,
+}
+
+fn register(line: &str) -> u64 {
+    to_u64(
+        line.split_once(": ")
+            .unwrap_or_else(|| panic!("Invalid register: {}", line))
+            .1,
+    )
+}
+
+impl From<&str> for Computer {
+    fn from(s: &str) -> Self {
+        let (registers, program) = s
+            .split_once("\n\n")
+            .expect("Input needs registers and a program separated by a blank line");
+        let registers = registers.lines().map(register).collect::<Vec<u64>>();
+        let program = program
+            .trim()
+            .trim_start_matches("Program: ")
+            .split(',')
+            .map(|value| {
+                value
+                    .trim()
+                    .parse::<u8>()
+                    .ok()
+                    .filter(|&v| v < 8)
+                    .unwrap_or_else(|| panic!("Invalid program value: {}", value))
+            })
+            .collect();
+        Computer {
+            a: registers[0],
+            b: registers[1],
+            c: registers[2],
+            program,
+        }
+    }
+}
+
+impl Computer {
+    fn combo(&self, operand: u8) -> u64 {
+        match operand {
+            0..=3 => operand as u64,
+            4 => self.a,
+            5 => self.b,
+            6 => self.c,
+            _ => panic!("Combo operand 7 is reserved"),
+        }
+    }
+
+    // shifting by the width of A or more leaves nothing
+    fn divide(&self, operand: u8) -> u64 {
+        u32::try_from(self.combo(operand))
+            .ok()
+            .and_then(|shift| self.a.checked_shr(shift))
+            .unwrap_or(0)
+    }
+
+    // runs until the instruction pointer leaves the program, returning the output, or None
+    // if the program is still running after MAX_INSTRUCTIONS
+    fn run(&mut self) -> Option<Vec<u8>> {
+        let mut output = Vec::new();
+        let mut ip = 0;
+        for _ in 0..MAX_INSTRUCTIONS {
+            if ip + 1 >= self.program.len() {
+                return Some(output);
+            }
+            let operand = self.program[ip + 1];
+            ip += 2;
+            match Opcode::from(self.program[ip - 2]) {
+                Opcode::Adv => self.a = self.divide(operand),
+                Opcode::Bxl => self.b ^= operand as u64,
+                Opcode::Bst => self.b = self.combo(operand) % 8,
+                Opcode::Jnz if self.a != 0 => ip = operand as usize,
+                Opcode::Jnz => {}
+                Opcode::Bxc => self.b ^= self.c,
+                Opcode::Out => output.push((self.combo(operand) % 8) as u8),
+                Opcode::Bdv => self.b = self.divide(operand),
+                Opcode::Cdv => self.c = self.divide(operand),
+            }
+        }
+        None
+    }
+
+    fn output_for(&self, a: u64) -> Option<Vec<u8>> {
+        Computer { a, ..self.clone() }.run()
+    }
+}
+
+fn combo_name(operand: u8) -> String {
+    match operand {
+        0..=3 => operand.to_string(),
+        4 => "A".to_string(),
+        5 => "B".to_string(),
+        6 => "C".to_string(),
+        _ => "?".to_string(),
+    }
+}
+
+fn describe(opcode: Opcode, operand: u8) -> String {
+    let combo = combo_name(operand);
+    match opcode {
+        Opcode::Adv => format!("A = A >> {}", combo),
+        Opcode::Bxl => format!("B = B ^ {}", operand),
+        Opcode::Bst => format!("B = {} % 8", combo),
+        Opcode::Jnz => format!("if A != 0 jump to {}", operand),
+        Opcode::Bxc => "B = B ^ C".to_string(),
+        Opcode::Out => format!("output {} % 8", combo),
+        Opcode::Bdv => format!("B = A >> {}", combo),
+        Opcode::Cdv => format!("C = A >> {}", combo),
+    }
+}
+
+// each instruction with its address, the operand as the opcode reads it, and its effect
+fn disassemble_program(program: &[u8]) -> String {
+    program
+        .chunks(2)
+        .enumerate()
+        .map(|(i, instruction)| {
+            let opcode = Opcode::from(instruction[0]);
+            let Some(&operand) = instruction.get(1) else {
+                return format!("{:>3}: {} (missing operand)", i * 2, opcode.mnemonic());
+            };
+            let shown = match opcode {
+                Opcode::Adv | Opcode::Bst | Opcode::Out | Opcode::Bdv | Opcode::Cdv => {
+                    combo_name(operand)
+                }
+                _ => operand.to_string(),
+            };
+            format!(
+                "{:>3}: {} {:<3} ; {}",
+                i * 2,
+                opcode.mnemonic(),
+                shown,
+                describe(opcode, operand)
+            )
+        })
+        .join("\n")
+}
+
+// the program is assumed to be a loop that outputs a value derived from the low bits of
+// A, shifts A right by three bits and repeats until A is zero. the last output then only
+// depends on the highest three bits of A, the one before it on the next three as well and
+// so on, so A is built up three bits at a time from the end of the program, keeping every
+// prefix that reproduces the tail of the program seen so far
+fn lowest_quine(computer: &Computer) -> Option<u64> {
+    let mut candidates = vec![0u64];
+    for i in (0..computer.program.len()).rev() {
+        candidates = candidates
+            .iter()
+            .flat_map(|&prefix| (0..8).map(move |bits| prefix << 3 | bits))
+            .filter(|&a| {
+                computer
+                    .output_for(a)
+                    .is_some_and(|output| output == computer.program[i..])
+            })
+            .collect();
+    }
+    candidates.into_iter().min()
+}
+
+pub fn disassemble(input: &str) -> String {
+    disassemble_program(&Computer::from(input).program)
+}
+
+pub fn part_a(input: &str) -> String {
+    Computer::from(input)
+        .run()
+        .unwrap_or_else(|| {
+            panic!(
+                "Program still running after {} instructions",
+                MAX_INSTRUCTIONS
+            )
+        })
+        .iter()
+        .join(",")
+}
+
+pub fn part_b(input: &str) -> u64 {
+    lowest_quine(&Computer::from(input))
+        .unwrap_or_else(|| panic!("No value of A makes the program output itself"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use advent_of_code_2024::{read_test_file, trimmed_lines};
+
+    fn computer(a: u64, b: u64, c: u64, program: &[u8]) -> Computer {
+        Computer {
+            a,
+            b,
+            c,
+            program: program.to_vec(),
+        }
+    }
+
+    #[test]
+    fn test_parse() {
+        let input = read_test_file(17);
+        assert_eq!(
+            Computer::from(input.as_str()),
+            computer(729, 0, 0, &[0, 1, 5, 4, 3, 0])
+        );
+    }
+
+    #[test]
+    fn test_run() {
+        let mut c = computer(0, 0, 9, &[2, 6]);
+        c.run();
+        assert_eq!(c.b, 1);
+
+        assert_eq!(
+            computer(10, 0, 0, &[5, 0, 5, 1, 5, 4]).run(),
+            Some(vec![0, 1, 2])
+        );
+
+        let mut c = computer(2024, 0, 0, &[0, 1, 5, 4, 3, 0]);
+        assert_eq!(c.run(), Some(vec![4, 2, 5, 6, 7, 7, 7, 7, 3, 1, 0]));
+        assert_eq!(c.a, 0);
+
+        let mut c = computer(0, 29, 0, &[1, 7]);
+        c.run();
+        assert_eq!(c.b, 26);
+
+        let mut c = computer(0, 2024, 43690, &[4, 0]);
+        c.run();
+        assert_eq!(c.b, 44354);
+    }
+
+    #[test]
+    fn test_divide_by_large_shift() {
+        let mut c = computer(12345, 1 << 32, 0, &[0, 5]);
+        c.run();
+        assert_eq!(c.a, 0);
+
+        let mut c = computer(12345, 0, 1 << 32, &[6, 6, 7, 6]);
+        c.run();
+        assert_eq!((c.b, c.c), (0, 0));
+    }
+
+    #[test]
+    fn test_run_step_limit() {
+        // A is never shifted, so the jump back repeats forever
+        let c = computer(1, 0, 0, &[1, 1, 5, 5, 3, 0]);
+        assert_eq!(c.output_for(1), None);
+        assert_eq!(lowest_quine(&c), None);
+    }
+
+    #[test]
+    fn test_disassemble() {
+        let expected = r#"
+            0: bst A   ; B = A % 8
+            2: bxl 5   ; B = B ^ 5
+            4: cdv B   ; C = A >> B
+            6: adv 3   ; A = A >> 3
+            8: bxc 0   ; B = B ^ C
+           10: out B   ; output B % 8
+           12: jnz 0   ; if A != 0 jump to 0
+        "#;
+        assert_eq!(
+            trimmed_lines(&disassemble_program(&[
+                2, 4, 1, 5, 7, 5, 0, 3, 4, 0, 5, 5, 3, 0
+            ])),
+            trimmed_lines(expected)
+        );
+    }
+
+    #[test]
+    fn test_lowest_quine() {
+        let c = computer(0, 0, 0, &[2, 4, 1, 1, 7, 5, 1, 5, 4, 0, 0, 3, 5, 5, 3, 0]);
+        assert_eq!(lowest_quine(&c), Some(164541160582845));
+        assert_eq!(c.output_for(164541160582845), Some(c.program.clone()));
+        assert_eq!(
+            lowest_quine(&computer(
+                0,
+                0,
+                0,
+                &[2, 4, 1, 5, 7, 5, 0, 3, 4, 0, 5, 5, 3, 0]
+            )),
+            None
+        );
+    }
+
+    #[test]
+    fn test_part_a() {
+        let input = read_test_file(17);
+        assert_eq!(part_a(&input), "4,6,3,5,6,3,5,2,1,0");
+    }
+
+    #[test]
+    fn test_part_b() {
+        let input = "Register A: 2024\nRegister B: 0\nRegister C: 0\n\nProgram: 0,3,5,4,3,0";
+        assert_eq!(part_b(input), 117440);
+    }
+}
@@ -1,4 +1,5 @@
 use advent_of_code_2024::to_i64;
+use itertools::Itertools;
 
 fn line_to_i64_vec(line: &str) -> Vec<i64> {
     line.split_whitespace().map(to_i64).collect::<Vec<i64>>()
@@ -7,85 +8,393 @@ fn line_to_i64_vec(line: &str) -> Vec<i64> {
 fn parse_lines(input: &str) -> Vec<Vec<i64>> {
     input
         .lines()
-        .map(|s| line_to_i64_vec(s))
+        .map(line_to_i64_vec)
         .collect::<Vec<Vec<i64>>>()
 }
 
-fn line_is_safe(line: &Vec<i64>) -> bool {
-    let is_ascending = line[0] < line[1];
+#[derive(PartialEq, Clone, Copy, Debug)]
+pub enum Direction {
+    Ascending,
+    Descending,
+    Either,
+}
 
-    for pair in line.windows(2) {
-        let diff = pair[1] - pair[0];
-        let abs_diff = diff.abs();
+impl From<&str> for Direction {
+    fn from(s: &str) -> Self {
+        match s {
+            "ascending" => Direction::Ascending,
+            "descending" => Direction::Descending,
+            "either" => Direction::Either,
+            _ => panic!("Invalid direction: {}", s),
+        }
+    }
+}
 
-        if is_ascending && diff < 0 {
-            return false;
-        } else if !is_ascending && diff > 0 {
-            return false;
-        } else if abs_diff > 3 || abs_diff < 1 {
-            return false;
+impl Direction {
+    // the candidate orders a report may follow, as "is ascending" flags
+    fn orders(&self) -> Vec<bool> {
+        match self {
+            Direction::Ascending => vec![true],
+            Direction::Descending => vec![false],
+            Direction::Either => vec![true, false],
         }
     }
-    true
 }
 
-fn line_combination_is_safe(line: &Vec<i64>) -> bool {
-    let line_combinations_with_one_removed = (0..line.len())
-        .map(|i| [&line[..i], &line[i + 1..]].concat())
-        .collect::<Vec<Vec<i64>>>();
-    let combinations_with_original =
-        [&line_combinations_with_one_removed[..], &[line.clone()]].concat();
-    combinations_with_original
-        .iter()
-        .map(line_is_safe)
-        .any(|b| b)
+// `min_step` and `max_step` bound the size of non-flat steps; whether a repeated level
+// is allowed is controlled by `allow_flat` alone
+#[derive(PartialEq, Clone, Debug)]
+pub struct SafetyPolicy {
+    min_step: i64,
+    max_step: i64,
+    direction: Direction,
+    max_removals: usize,
+    allow_flat: bool,
 }
 
-pub fn part_a(input: &str) -> u32 {
+static PART_A_POLICY: SafetyPolicy = SafetyPolicy {
+    min_step: 1,
+    max_step: 3,
+    direction: Direction::Either,
+    max_removals: 0,
+    allow_flat: false,
+};
+
+static PART_B_POLICY: SafetyPolicy = SafetyPolicy {
+    max_removals: 1,
+    ..PART_A_POLICY
+};
+
+// comma-separated settings on top of the part a policy, e.g.
+// `steps=1-5,direction=ascending,removals=2,flat`
+impl From<&str> for SafetyPolicy {
+    fn from(s: &str) -> Self {
+        let mut policy = PART_A_POLICY.clone();
+        for setting in s.split(',').map(str::trim).filter(|s| !s.is_empty()) {
+            match setting.split_once('=') {
+                Some(("steps", range)) => {
+                    let (min, max) = range
+                        .split_once('-')
+                        .unwrap_or_else(|| panic!("Invalid step range: {}", range));
+                    policy.min_step = to_i64(min);
+                    policy.max_step = to_i64(max);
+                }
+                Some(("direction", direction)) => policy.direction = direction.into(),
+                Some(("removals", removals)) => {
+                    policy.max_removals = removals
+                        .parse()
+                        .unwrap_or_else(|_| panic!("Invalid removal count: {}", removals))
+                }
+                None if setting == "flat" => policy.allow_flat = true,
+                _ => panic!("Invalid policy setting: {}", setting),
+            }
+        }
+        policy
+    }
+}
+
+// the indices of the offending pair of levels
+#[derive(PartialEq, Debug)]
+enum Violation {
+    Direction(usize, usize),
+    Step(usize, usize),
+}
+
+impl SafetyPolicy {
+    fn step_is_valid(&self, from: i64, to: i64, ascending: bool) -> bool {
+        let diff = to - from;
+        if diff == 0 {
+            return self.allow_flat;
+        }
+        let signed = if ascending { diff } else { -diff };
+        signed > 0 && (self.min_step..=self.max_step).contains(&signed)
+    }
+
+    // checks every pair of levels in order; unless the policy fixes the direction it is
+    // set by the first non-flat pair
+    fn first_violation(&self, line: &[i64]) -> Option<Violation> {
+        let mut is_ascending = match self.direction {
+            Direction::Ascending => Some(true),
+            Direction::Descending => Some(false),
+            Direction::Either => None,
+        };
+
+        for (a, b) in (0..line.len()).tuple_windows() {
+            let diff = line[b] - line[a];
+            if diff == 0 {
+                if self.allow_flat {
+                    continue;
+                }
+                return Some(Violation::Step(a, b));
+            }
+            let ascending = *is_ascending.get_or_insert(diff > 0);
+
+            if ascending != (diff > 0) {
+                return Some(Violation::Direction(a, b));
+            } else if !self.step_is_valid(line[a], line[b], ascending) {
+                return Some(Violation::Step(a, b));
+            }
+        }
+        None
+    }
+
+    // the fewest levels to remove for the report to follow one order, or None if that
+    // takes more than `max_removals`.
+    //
+    // removed[j] is the fewest removals before j for a safe run of kept levels ending at
+    // j. a kept predecessor can be at most max_removals + 1 levels back, so each order
+    // takes O(n * k)
+    fn removals_for_order(&self, line: &[i64], ascending: bool) -> Option<Vec<usize>> {
+        let k = self.max_removals;
+        let mut removed: Vec<Option<usize>> = vec![None; line.len()];
+        let mut previous: Vec<Option<usize>> = vec![None; line.len()];
+
+        for j in 0..line.len() {
+            if j <= k {
+                removed[j] = Some(j);
+            }
+            for i in (j.saturating_sub(k + 1)..j).rev() {
+                let Some(before) = removed[i] else { continue };
+                let cost = before + (j - i - 1);
+                if cost <= k
+                    && removed[j].is_none_or(|best| cost < best)
+                    && self.step_is_valid(line[i], line[j], ascending)
+                {
+                    removed[j] = Some(cost);
+                    previous[j] = Some(i);
+                }
+            }
+        }
+
+        let mut best: Option<(usize, usize)> = None;
+        for j in (0..line.len()).rev() {
+            let Some(before) = removed[j] else { continue };
+            let cost = before + (line.len() - 1 - j);
+            if cost <= k && best.is_none_or(|(_, best_cost)| cost < best_cost) {
+                best = Some((j, cost));
+            }
+        }
+
+        let Some((last, _)) = best else {
+            return if line.is_empty() { Some(vec![]) } else { None };
+        };
+        let mut kept = vec![false; line.len()];
+        let mut current = Some(last);
+        while let Some(j) = current {
+            kept[j] = true;
+            current = previous[j];
+        }
+        Some((0..line.len()).filter(|&i| !kept[i]).collect())
+    }
+
+    // the indices of the levels to remove, preferring fewer removals and then ascending
+    fn fix(&self, line: &[i64]) -> Option<Vec<usize>> {
+        self.direction
+            .orders()
+            .into_iter()
+            .filter_map(|ascending| self.removals_for_order(line, ascending))
+            .min_by_key(Vec::len)
+    }
+
+    fn is_safe(&self, line: &[i64]) -> bool {
+        self.fix(line).is_some()
+    }
+
+    fn describe_violation(&self, line: &[i64], violation: &Violation) -> String {
+        match *violation {
+            Violation::Direction(a, b) => format!(
+                "{} -> {} breaks the {} order",
+                line[a],
+                line[b],
+                if line[b] > line[a] {
+                    "descending"
+                } else {
+                    "ascending"
+                }
+            ),
+            Violation::Step(a, b) => format!(
+                "{} -> {} changes by {}, outside {}-{}",
+                line[a],
+                line[b],
+                (line[b] - line[a]).abs(),
+                self.min_step,
+                self.max_step
+            ),
+        }
+    }
+
+    fn explain_line(&self, line: &[i64]) -> String {
+        let levels = line.iter().join(" ");
+        let Some(violation) = self.first_violation(line) else {
+            return format!("{}: safe", levels);
+        };
+        let reason = self.describe_violation(line, &violation);
+        if self.max_removals == 0 {
+            return format!("{}: unsafe, {}", levels, reason);
+        }
+        match self.fix(line) {
+            Some(removed) => format!(
+                "{}: {}, safe after removing {} {}",
+                levels,
+                reason,
+                if removed.len() == 1 {
+                    "level"
+                } else {
+                    "levels"
+                },
+                removed
+                    .iter()
+                    .map(|&i| format!("{} ({})", i + 1, line[i]))
+                    .join(", ")
+            ),
+            None if self.max_removals == 1 => {
+                format!("{}: unsafe, {}, no single removal fixes it", levels, reason)
+            }
+            None => format!(
+                "{}: unsafe, {}, removing {} levels does not fix it",
+                levels, reason, self.max_removals
+            ),
+        }
+    }
+}
+
+pub fn check(input: &str, policy: &SafetyPolicy) -> u32 {
     let lines = parse_lines(input);
-    let line_safety = lines
-        .into_iter()
-        .map(|l| line_is_safe(&l))
-        .collect::<Vec<bool>>();
-    line_safety.into_iter().filter(|&b| b).count() as u32
+    lines.iter().filter(|l| policy.is_safe(l)).count() as u32
+}
+
+pub fn part_a(input: &str) -> u32 {
+    check(input, &PART_A_POLICY)
 }
 
 pub fn part_b(input: &str) -> u32 {
-    let lines = parse_lines(input);
-    let line_safety = lines
-        .into_iter()
-        .map(|l| line_combination_is_safe(&l))
-        .collect::<Vec<bool>>();
-    line_safety.into_iter().filter(|&b| b).count() as u32
+    check(input, &PART_B_POLICY)
+}
+
+pub fn explain_check(input: &str, policy: &SafetyPolicy) -> String {
+    parse_lines(input)
+        .iter()
+        .map(|l| policy.explain_line(l))
+        .join("\n")
+}
+
+pub fn explain(input: &str, part: char) -> String {
+    let policy = if part == 'b' {
+        &PART_B_POLICY
+    } else {
+        &PART_A_POLICY
+    };
+    explain_check(input, policy)
 }
 
 #[cfg(test)]
+#[allow(clippy::bool_assert_comparison)]
 mod tests {
     use super::*;
-    use advent_of_code_2024::read_test_file;
+    use advent_of_code_2024::{read_test_file, trimmed_lines};
 
     #[test]
     fn test_line_safety_simple_ascending() {
         let input = vec![1, 2, 3, 4];
-        assert_eq!(line_is_safe(&input), true);
+        assert_eq!(PART_A_POLICY.is_safe(&input), true);
     }
 
     #[test]
     fn test_line_safety_simple_descending() {
         let input = vec![5, 4, 3];
-        assert_eq!(line_is_safe(&input), true);
+        assert_eq!(PART_A_POLICY.is_safe(&input), true);
     }
 
     #[test]
     fn test_line_safety_start_descending() {
         let input = vec![5, 4, 5, 6];
-        assert_eq!(line_is_safe(&input), false);
+        assert_eq!(PART_A_POLICY.is_safe(&input), false);
     }
 
     #[test]
     fn test_line_safety_repeating_value() {
         let input = vec![8, 6, 4, 4, 1];
-        assert_eq!(line_is_safe(&input), false);
+        assert_eq!(PART_A_POLICY.is_safe(&input), false);
+    }
+
+    #[test]
+    fn test_first_violation() {
+        assert_eq!(
+            PART_A_POLICY.first_violation(&[1, 2, 7, 8, 9]),
+            Some(Violation::Step(1, 2))
+        );
+        assert_eq!(
+            PART_A_POLICY.first_violation(&[1, 3, 2, 4, 5]),
+            Some(Violation::Direction(1, 2))
+        );
+        assert_eq!(
+            SafetyPolicy::from("direction=descending").first_violation(&[1, 3, 2]),
+            Some(Violation::Direction(0, 1))
+        );
+        assert_eq!(SafetyPolicy::from("flat").first_violation(&[4, 4, 3]), None);
+    }
+
+    #[test]
+    fn test_fix() {
+        assert_eq!(PART_B_POLICY.fix(&[1, 3, 2, 4, 5]), Some(vec![1]));
+        assert_eq!(PART_B_POLICY.fix(&[8, 6, 4, 4, 1]), Some(vec![2]));
+        assert_eq!(PART_B_POLICY.fix(&[5, 6, 4, 3, 2]), Some(vec![0]));
+        assert_eq!(PART_B_POLICY.fix(&[1, 2, 3, 4, 9]), Some(vec![4]));
+        assert_eq!(PART_B_POLICY.fix(&[1, 2, 7, 8, 9]), None);
+        assert_eq!(PART_B_POLICY.fix(&[7, 6, 4, 2, 1]), Some(vec![]));
+    }
+
+    #[test]
+    fn test_fix_multiple_removals() {
+        let policy = SafetyPolicy::from("removals=2");
+        assert_eq!(policy.fix(&[1, 9, 2, 9, 3, 4]), Some(vec![1, 3]));
+        assert_eq!(policy.fix(&[1, 9, 9, 2, 3]), Some(vec![1, 2]));
+        assert_eq!(policy.fix(&[1, 9, 9, 9, 2]), None);
+        assert_eq!(policy.fix(&[9, 1, 2, 3]), Some(vec![0]));
+    }
+
+    #[test]
+    fn test_policy_settings() {
+        let policy = SafetyPolicy::from("steps=2-4,direction=ascending,removals=3,flat");
+        assert_eq!(
+            policy,
+            SafetyPolicy {
+                min_step: 2,
+                max_step: 4,
+                direction: Direction::Ascending,
+                max_removals: 3,
+                allow_flat: true,
+            }
+        );
+        assert!(policy.is_safe(&[1, 3, 3, 7]));
+        assert!(!SafetyPolicy::from("steps=2-4").is_safe(&[1, 2, 4]));
+        assert!(!SafetyPolicy::from("direction=ascending").is_safe(&[3, 2, 1]));
+        assert_eq!(SafetyPolicy::from(""), PART_A_POLICY);
+    }
+
+    #[test]
+    fn test_check() {
+        let input = read_test_file(2);
+        assert_eq!(check(&input, &SafetyPolicy::from("removals=2")), 6);
+        assert_eq!(check(&input, &SafetyPolicy::from("steps=1-4")), 3);
+    }
+
+    #[test]
+    fn test_explain() {
+        let input = read_test_file(2);
+        let expected = r#"
+            7 6 4 2 1: safe
+            1 2 7 8 9: unsafe, 2 -> 7 changes by 5, outside 1-3, no single removal fixes it
+            9 7 6 2 1: unsafe, 6 -> 2 changes by 4, outside 1-3, no single removal fixes it
+            1 3 2 4 5: 3 -> 2 breaks the ascending order, safe after removing level 2 (3)
+            8 6 4 4 1: 4 -> 4 changes by 0, outside 1-3, safe after removing level 3 (4)
+            1 3 6 7 9: safe
+        "#;
+        let expected_lines = trimmed_lines(expected);
+        assert_eq!(
+            explain(&input, 'b').lines().collect::<Vec<&str>>(),
+            expected_lines
+        );
     }
 
     #[test]
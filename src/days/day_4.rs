@@ -1,6 +1,34 @@
+use advent_of_code_2024::grid::build_grid;
+
 static XMAS: &str = "XMAS";
 static XMAS_REVERSED: &str = "SAMX";
 
+static DIRECTIONS: [(i64, i64); 8] = [
+    (1, 0),
+    (1, 1),
+    (0, 1),
+    (-1, 1),
+    (-1, 0),
+    (-1, -1),
+    (0, -1),
+    (1, -1),
+];
+
+pub enum HighlightStyle {
+    Ansi,
+    Mask,
+}
+
+impl From<&str> for HighlightStyle {
+    fn from(s: &str) -> Self {
+        match s {
+            "ansi" => HighlightStyle::Ansi,
+            "mask" => HighlightStyle::Mask,
+            _ => panic!("Invalid highlight style: {}", s),
+        }
+    }
+}
+
 fn transpose_input(input: &str) -> String {
     let rows: Vec<Vec<char>> = input.lines().map(|line| line.chars().collect()).collect();
     let transposed_input = (0..rows[0].len())
@@ -50,9 +78,7 @@ fn find_matches(input: &str) -> u32 {
     let mut xmas_count = 0;
     for (i, _) in input.char_indices() {
         let candidate = &input[i..(i + XMAS.len()).min(input.len())];
-        if candidate == XMAS {
-            xmas_count += 1;
-        } else if candidate == XMAS_REVERSED {
+        if candidate == XMAS || candidate == XMAS_REVERSED {
             xmas_count += 1;
         }
     }
@@ -81,15 +107,45 @@ pub fn part_a(input: &str) -> u32 {
     xmas_count
 }
 
-fn is_m_and_s(left: (usize, usize), right: (usize, usize), grid: &Vec<Vec<char>>) -> bool {
+fn is_m_and_s(left: (usize, usize), right: (usize, usize), grid: &[Vec<char>]) -> bool {
     let chars: (char, char) = (grid[left.0][left.1], grid[right.0][right.1]);
     chars == ('M', 'S') || chars == ('S', 'M')
 }
 
-pub fn part_b(input: &str) -> u32 {
-    let grid: Vec<Vec<char>> = input.lines().map(|line| line.chars().collect()).collect();
+// each match is the list of (row, column) cells that spell out the word
+fn xmas_matches(grid: &[Vec<char>]) -> Vec<Vec<(usize, usize)>> {
+    let height = grid.len() as i64;
+    let width = grid[0].len() as i64;
+    let mut matches = Vec::new();
 
-    let mut matches = 0;
+    for i in 0..height {
+        for j in 0..width {
+            for (di, dj) in DIRECTIONS.iter() {
+                let cells = (0..XMAS.len() as i64)
+                    .map(|step| (i + di * step, j + dj * step))
+                    .collect::<Vec<(i64, i64)>>();
+                let in_bounds = cells
+                    .iter()
+                    .all(|&(ci, cj)| ci >= 0 && ci < height && cj >= 0 && cj < width);
+                if !in_bounds {
+                    continue;
+                }
+                let cells = cells
+                    .into_iter()
+                    .map(|(ci, cj)| (ci as usize, cj as usize))
+                    .collect::<Vec<(usize, usize)>>();
+                let is_xmas = cells.iter().map(|&(ci, cj)| grid[ci][cj]).eq(XMAS.chars());
+                if is_xmas {
+                    matches.push(cells);
+                }
+            }
+        }
+    }
+    matches
+}
+
+fn x_mas_matches(grid: &[Vec<char>]) -> Vec<Vec<(usize, usize)>> {
+    let mut matches = Vec::new();
 
     for i in 1..grid.len() - 1 {
         for j in 1..grid[i].len() - 1 {
@@ -99,11 +155,11 @@ pub fn part_b(input: &str) -> u32 {
                 let top_right = (i - 1, j + 1);
                 let bottom_left = (i + 1, j - 1);
 
-                let valid_left_to_right = is_m_and_s(top_left, bottom_right, &grid);
-                let valid_right_to_left = is_m_and_s(top_right, bottom_left, &grid);
+                let valid_left_to_right = is_m_and_s(top_left, bottom_right, grid);
+                let valid_right_to_left = is_m_and_s(top_right, bottom_left, grid);
 
                 if valid_left_to_right && valid_right_to_left {
-                    matches += 1;
+                    matches.push(vec![top_left, top_right, (i, j), bottom_left, bottom_right]);
                 }
             }
         }
@@ -111,6 +167,71 @@ pub fn part_b(input: &str) -> u32 {
     matches
 }
 
+fn match_counts(grid: &[Vec<char>], matches: &[Vec<(usize, usize)>]) -> Vec<Vec<u32>> {
+    let mut counts = grid
+        .iter()
+        .map(|row| vec![0; row.len()])
+        .collect::<Vec<Vec<u32>>>();
+    for &(i, j) in matches.iter().flatten() {
+        counts[i][j] += 1;
+    }
+    counts
+}
+
+fn render_highlighted(grid: &[Vec<char>], counts: &[Vec<u32>], style: &HighlightStyle) -> String {
+    grid.iter()
+        .zip(counts.iter())
+        .map(|(row, row_counts)| {
+            row.iter()
+                .zip(row_counts.iter())
+                .map(|(&cell, &count)| match (style, count) {
+                    (HighlightStyle::Ansi, 0) => cell.to_string(),
+                    (HighlightStyle::Ansi, _) => format!("\x1b[1;31m{}\x1b[0m", cell),
+                    (HighlightStyle::Mask, 0) => ".".to_string(),
+                    (HighlightStyle::Mask, _) => cell.to_string(),
+                })
+                .collect::<String>()
+        })
+        .collect::<Vec<String>>()
+        .join("\n")
+}
+
+fn render_heatmap(counts: &[Vec<u32>]) -> String {
+    counts
+        .iter()
+        .map(|row| {
+            row.iter()
+                .map(|&count| match count {
+                    0 => '.',
+                    1..=9 => char::from_digit(count, 10).unwrap(),
+                    _ => '+',
+                })
+                .collect::<String>()
+        })
+        .collect::<Vec<String>>()
+        .join("\n")
+}
+
+pub fn part_b(input: &str) -> u32 {
+    let grid = build_grid(input);
+    x_mas_matches(&grid).len() as u32
+}
+
+pub fn render(input: &str, part: char, style: HighlightStyle) -> String {
+    let grid = build_grid(input);
+    let matches = match part {
+        'a' => xmas_matches(&grid),
+        'b' => x_mas_matches(&grid),
+        _ => panic!("Invalid part: {}", part),
+    };
+    let counts = match_counts(&grid, &matches);
+    format!(
+        "{}\n\n{}",
+        render_highlighted(&grid, &counts, &style),
+        render_heatmap(&counts)
+    )
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -144,7 +265,7 @@ mod tests {
         "#
         .replace(" ", "");
         let clean_expected = expected.trim();
-        let actual = transpose_input(&clean_input);
+        let actual = transpose_input(clean_input);
         assert_eq!(actual, clean_expected);
     }
 
@@ -166,7 +287,7 @@ mod tests {
         "#
         .replace(" ", "");
         let clean_expected = expected.trim();
-        let binding = reorder_to_diagonal_input(&clean_input);
+        let binding = reorder_to_diagonal_input(clean_input);
         let actual = binding.trim();
         assert_eq!(actual, clean_expected);
     }
@@ -183,7 +304,7 @@ mod tests {
         .replace(" ", "");
         let clean_input = input.trim();
         let expected = 3;
-        let binding = reorder_to_diagonal_input(&clean_input);
+        let binding = reorder_to_diagonal_input(clean_input);
         let actual = binding.trim();
         assert_eq!(expected, find_matches(actual));
     }
@@ -199,12 +320,81 @@ mod tests {
         .replace(" ", "");
         let clean_input = input.trim();
         let expected = 1;
-        let reversed_input = reverse_lines(&clean_input);
+        let reversed_input = reverse_lines(clean_input);
         let binding = reorder_to_diagonal_input(&reversed_input);
         let actual = binding.trim();
         assert_eq!(expected, find_matches(actual));
     }
 
+    #[test]
+    fn test_xmas_matches() {
+        let grid = build_grid(&read_test_file(4));
+        assert_eq!(xmas_matches(&grid).len(), 18);
+    }
+
+    #[test]
+    fn test_render_highlighted_mask_part_a() {
+        let grid = build_grid(&read_test_file(4));
+        let counts = match_counts(&grid, &xmas_matches(&grid));
+        let expected = r#"
+            ....XXMAS.
+            .SAMXMS...
+            ...S..A...
+            ..A.A.MS.X
+            XMASAMX.MM
+            X.....XA.A
+            S.S.S.S.SS
+            .A.A.A.A.A
+            ..M.M.M.MM
+            .X.X.XMASX
+        "#
+        .replace(" ", "");
+        let actual = render_highlighted(&grid, &counts, &HighlightStyle::Mask);
+        assert_eq!(actual, expected.trim());
+    }
+
+    #[test]
+    fn test_render_highlighted_mask_part_b() {
+        let grid = build_grid(&read_test_file(4));
+        let counts = match_counts(&grid, &x_mas_matches(&grid));
+        let expected = r#"
+            .M.S......
+            ..A..MSMS.
+            .M.S.MAA..
+            ..A.ASMSM.
+            .M.S.M....
+            ..........
+            S.S.S.S.S.
+            .A.A.A.A..
+            M.M.M.M.M.
+            ..........
+        "#
+        .replace(" ", "");
+        let actual = render_highlighted(&grid, &counts, &HighlightStyle::Mask);
+        assert_eq!(actual, expected.trim());
+    }
+
+    #[test]
+    fn test_render_heatmap() {
+        let input = r#"
+            XMAS
+            MM##
+            A#A#
+            S##S
+        "#
+        .replace(" ", "");
+        let grid = build_grid(input.trim());
+        let counts = match_counts(&grid, &xmas_matches(&grid));
+        let expected = r#"
+            3111
+            11..
+            1.1.
+            1..1
+        "#
+        .replace(" ", "");
+        assert_eq!(render_heatmap(&counts), expected.trim());
+    }
+
     #[test]
     fn test_part_a() {
         let input = read_test_file(4);
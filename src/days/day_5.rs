@@ -1,4 +1,8 @@
 use advent_of_code_2024::to_u32;
+use itertools::Itertools;
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap, HashSet};
+use std::fmt;
 
 #[derive(PartialEq, Debug)]
 struct Rule {
@@ -8,7 +12,7 @@ struct Rule {
 
 impl From<&str> for Rule {
     fn from(s: &str) -> Self {
-        let parts = s.split("|").into_iter().map(to_u32).collect::<Vec<u32>>();
+        let parts = s.split("|").map(to_u32).collect::<Vec<u32>>();
         let before = parts[0];
         let after = parts[1];
         Rule {
@@ -18,6 +22,27 @@ impl From<&str> for Rule {
     }
 }
 
+impl fmt::Display for Rule {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}|{}", self.candidate, self.before)
+    }
+}
+
+pub enum GraphFormat {
+    Dot,
+    Mermaid,
+}
+
+impl From<&str> for GraphFormat {
+    fn from(s: &str) -> Self {
+        match s {
+            "dot" => GraphFormat::Dot,
+            "mermaid" => GraphFormat::Mermaid,
+            _ => panic!("Invalid graph format: {}", s),
+        }
+    }
+}
+
 struct RulesAndUpdates {
     rules: Vec<Rule>,
     updates: Vec<Vec<u32>>,
@@ -37,65 +62,373 @@ fn rules_and_updates(input: &str) -> RulesAndUpdates {
     RulesAndUpdates { rules, updates }
 }
 
-fn process_update_part_a(update: Vec<u32>, rules: &Vec<Rule>) -> Option<u32> {
-    for (index, &element) in update.iter().enumerate() {
-        let preceding = &update[..index];
-        let violates_rule = rules
-            .iter()
-            .any(|rule| rule.candidate == element && preceding.contains(&rule.before));
-        if violates_rule {
-            return None;
+struct RuleSet {
+    successors: HashMap<u32, HashSet<u32>>,
+}
+
+impl From<&[Rule]> for RuleSet {
+    fn from(rules: &[Rule]) -> Self {
+        let mut successors: HashMap<u32, HashSet<u32>> = HashMap::new();
+        for rule in rules {
+            successors
+                .entry(rule.candidate)
+                .or_default()
+                .insert(rule.before);
         }
+        RuleSet { successors }
     }
-    let middle_index = update.len() / 2;
-    update.get(middle_index).copied()
 }
 
-fn process_update_part_b(update: Vec<u32>, rules: &Vec<Rule>) -> u32 {
-    for (index, &element) in update.iter().enumerate() {
-        let preceding = &update[..index];
-        let violates_rule = rules
+impl RuleSet {
+    fn must_precede(&self, a: u32, b: u32) -> bool {
+        self.successors
+            .get(&a)
+            .is_some_and(|successors| successors.contains(&b))
+    }
+
+    fn is_ordered(&self, update: &[u32]) -> bool {
+        update
+            .iter()
+            .tuple_combinations()
+            .all(|(&a, &b)| !self.must_precede(b, a))
+    }
+
+    // topological sort of the update's pages, preferring the original order where
+    // the rules leave it unconstrained; returns None if the rules form a cycle
+    fn sort_update(&self, update: &[u32]) -> Option<Vec<u32>> {
+        let mut in_degree = vec![0; update.len()];
+        for (i, j) in (0..update.len()).tuple_combinations() {
+            if self.must_precede(update[i], update[j]) {
+                in_degree[j] += 1;
+            }
+            if self.must_precede(update[j], update[i]) {
+                in_degree[i] += 1;
+            }
+        }
+
+        let mut ready = (0..update.len())
+            .filter(|&i| in_degree[i] == 0)
+            .map(Reverse)
+            .collect::<BinaryHeap<Reverse<usize>>>();
+        let mut sorted = Vec::with_capacity(update.len());
+        while let Some(Reverse(i)) = ready.pop() {
+            sorted.push(update[i]);
+            for j in 0..update.len() {
+                if in_degree[j] > 0 && self.must_precede(update[i], update[j]) {
+                    in_degree[j] -= 1;
+                    if in_degree[j] == 0 {
+                        ready.push(Reverse(j));
+                    }
+                }
+            }
+        }
+
+        if sorted.len() == update.len() {
+            Some(sorted)
+        } else {
+            None
+        }
+    }
+
+    fn pages(&self) -> Vec<u32> {
+        self.successors
+            .iter()
+            .flat_map(|(&page, successors)| successors.iter().copied().chain([page]))
+            .unique()
+            .sorted()
+            .collect()
+    }
+
+    // every page reachable from `from` following rules between the given pages only
+    fn reachable(&self, from: u32, pages: &HashSet<u32>) -> HashSet<u32> {
+        let mut reached = HashSet::new();
+        let mut stack = vec![from];
+        while let Some(page) = stack.pop() {
+            for &next in self.successors.get(&page).into_iter().flatten() {
+                if pages.contains(&next) && reached.insert(next) {
+                    stack.push(next);
+                }
+            }
+        }
+        reached
+    }
+
+    // groups of pages that must each precede one another, restricted to the given pages
+    fn cycles(&self, pages: &[u32]) -> Vec<Vec<u32>> {
+        let page_set = pages.iter().copied().collect::<HashSet<u32>>();
+        let reachable = pages
+            .iter()
+            .map(|&page| (page, self.reachable(page, &page_set)))
+            .collect::<HashMap<u32, HashSet<u32>>>();
+
+        let mut cycles = Vec::new();
+        let mut seen = HashSet::new();
+        for &page in pages.iter().sorted() {
+            if seen.contains(&page) || !reachable[&page].contains(&page) {
+                continue;
+            }
+            let cycle = reachable[&page]
+                .iter()
+                .copied()
+                .filter(|other| reachable[other].contains(&page))
+                .sorted()
+                .collect::<Vec<u32>>();
+            seen.extend(cycle.iter().copied());
+            cycles.push(cycle);
+        }
+        cycles
+    }
+
+    // rules between the given pages that are already implied by a longer chain of rules.
+    //
+    // pages in the same cycle all reach one another, so any rule among them would look
+    // implied even though removing it can break the cycle. redundancy is therefore judged
+    // on the condensed graph, where each cycle is one node: a rule is redundant only if
+    // its pages lie in different nodes and a chain through a third node links them
+    fn redundant_rules(&self, pages: &[u32]) -> Vec<Rule> {
+        let page_set = pages.iter().copied().collect::<HashSet<u32>>();
+        let reachable = pages
+            .iter()
+            .map(|&page| (page, self.reachable(page, &page_set)))
+            .collect::<HashMap<u32, HashSet<u32>>>();
+        let same_node =
+            |a: u32, b: u32| a == b || (reachable[&a].contains(&b) && reachable[&b].contains(&a));
+
+        let mut redundant = Vec::new();
+        for &candidate in pages.iter().sorted() {
+            let direct = self
+                .successors
+                .get(&candidate)
+                .into_iter()
+                .flatten()
+                .copied()
+                .filter(|page| page_set.contains(page))
+                .collect::<Vec<u32>>();
+            for &before in direct.iter().sorted() {
+                if same_node(candidate, before) {
+                    continue;
+                }
+                let implied = reachable[&candidate].iter().any(|&via| {
+                    !same_node(via, candidate)
+                        && !same_node(via, before)
+                        && reachable[&via].contains(&before)
+                });
+                if implied {
+                    redundant.push(Rule { candidate, before });
+                }
+            }
+        }
+        redundant
+    }
+
+    // pairs of pages in the update whose relative order no chain of rules decides
+    fn unconstrained_pairs(&self, update: &[u32]) -> Vec<(u32, u32)> {
+        let page_set = update.iter().copied().collect::<HashSet<u32>>();
+        let reachable = update
             .iter()
-            .any(|rule| rule.candidate == element && preceding.contains(&rule.before));
-        if violates_rule {
-            let mut reordered_update = update.clone();
-            reordered_update.swap(index, index - 1);
-            return process_update_part_b(reordered_update, rules);
+            .map(|&page| (page, self.reachable(page, &page_set)))
+            .collect::<HashMap<u32, HashSet<u32>>>();
+
+        update
+            .iter()
+            .tuple_combinations()
+            .filter(|(a, b)| !reachable[a].contains(b) && !reachable[b].contains(a))
+            .map(|(&a, &b)| (a, b))
+            .collect()
+    }
+}
+
+struct UpdateAnalysis {
+    update: Vec<u32>,
+    ordered: bool,
+    cycles: Vec<Vec<u32>>,
+    unconstrained_pairs: Vec<(u32, u32)>,
+}
+
+struct Analysis {
+    cycles: Vec<Vec<u32>>,
+    redundant_rules: Vec<Rule>,
+    updates: Vec<UpdateAnalysis>,
+}
+
+impl fmt::Display for Analysis {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "Global cycles: {:?}", self.cycles)?;
+        writeln!(
+            f,
+            "Redundant rules ({}): {}",
+            self.redundant_rules.len(),
+            self.redundant_rules.iter().join(", ")
+        )?;
+        for (index, update) in self.updates.iter().enumerate() {
+            let ordering = if !update.cycles.is_empty() {
+                "no valid ordering"
+            } else if !update.unconstrained_pairs.is_empty() {
+                "multiple valid orderings"
+            } else {
+                "unique ordering"
+            };
+            writeln!(
+                f,
+                "Update {} {:?}: {}, {}, cycles {:?}, unconstrained pairs {:?}",
+                index + 1,
+                update.update,
+                if update.ordered {
+                    "ordered"
+                } else {
+                    "unordered"
+                },
+                ordering,
+                update.cycles,
+                update.unconstrained_pairs
+            )?;
         }
+        Ok(())
     }
-    let middle_index = update.len() / 2;
-    update.get(middle_index).unwrap().to_owned()
+}
+
+fn analyse_rules(parsed_input: &RulesAndUpdates) -> Analysis {
+    let rule_set = RuleSet::from(&parsed_input.rules[..]);
+    let pages = rule_set.pages();
+    let updates = parsed_input
+        .updates
+        .iter()
+        .map(|update| UpdateAnalysis {
+            update: update.clone(),
+            ordered: rule_set.is_ordered(update),
+            cycles: rule_set.cycles(update),
+            unconstrained_pairs: rule_set.unconstrained_pairs(update),
+        })
+        .collect();
+    Analysis {
+        cycles: rule_set.cycles(&pages),
+        redundant_rules: rule_set.redundant_rules(&pages),
+        updates,
+    }
+}
+
+fn middle_page(update: &[u32]) -> u32 {
+    update[update.len() / 2]
+}
+
+fn process_update_part_a(update: &[u32], rule_set: &RuleSet) -> Option<u32> {
+    if rule_set.is_ordered(update) {
+        Some(middle_page(update))
+    } else {
+        None
+    }
+}
+
+// returns the corrected update, or None if the update was already in order
+fn process_update_part_b(update: &[u32], rule_set: &RuleSet) -> Option<Vec<u32>> {
+    if rule_set.is_ordered(update) {
+        return None;
+    }
+    let corrected = rule_set
+        .sort_update(update)
+        .unwrap_or_else(|| panic!("Rules contain a cycle for update {:?}", update));
+    Some(corrected)
 }
 
 pub fn part_a(input: &str) -> u32 {
     let parsed_input = rules_and_updates(input);
-    let rules = parsed_input.rules;
-    let updates = parsed_input.updates;
-    updates
-        .into_iter()
-        .filter_map(|update| process_update_part_a(update, &rules))
+    let rule_set = RuleSet::from(&parsed_input.rules[..]);
+    parsed_input
+        .updates
+        .iter()
+        .filter_map(|update| process_update_part_a(update, &rule_set))
         .sum()
 }
 
 pub fn part_b(input: &str) -> u32 {
     let parsed_input = rules_and_updates(input);
-    let rules = parsed_input.rules;
-    let updates = parsed_input.updates;
-    let part_a_sum: u32 = updates
+    let rule_set = RuleSet::from(&parsed_input.rules[..]);
+    parsed_input
+        .updates
         .iter()
-        .filter_map(|update| process_update_part_a(update.to_vec(), &rules))
-        .sum();
-    let part_b_sum: u32 = updates
-        .into_iter()
-        .map(|update| process_update_part_b(update, &rules))
-        .sum();
-    part_b_sum - part_a_sum
+        .filter_map(|update| process_update_part_b(update, &rule_set))
+        .map(|corrected| middle_page(&corrected))
+        .sum()
+}
+
+// rules as (rule, violated) edges, restricted to the update's pages when one is given
+fn rule_edges<'a>(rules: &'a [Rule], update: Option<&[u32]>) -> Vec<(&'a Rule, bool)> {
+    rules
+        .iter()
+        .filter_map(|rule| match update {
+            None => Some((rule, false)),
+            Some(update) => {
+                let candidate_index = update.iter().position(|&page| page == rule.candidate)?;
+                let before_index = update.iter().position(|&page| page == rule.before)?;
+                Some((rule, before_index < candidate_index))
+            }
+        })
+        .collect()
+}
+
+fn render_graph(rules: &[Rule], update: Option<&[u32]>, format: &GraphFormat) -> String {
+    let edges = rule_edges(rules, update);
+    let pages = match update {
+        Some(update) => update.to_vec(),
+        None => RuleSet::from(rules).pages(),
+    };
+
+    let mut lines = Vec::new();
+    match format {
+        GraphFormat::Dot => {
+            lines.push("digraph rules {".to_string());
+            lines.extend(pages.iter().map(|page| format!("    {};", page)));
+            lines.extend(edges.iter().map(|(rule, violated)| {
+                let attributes = if *violated { " [color=red]" } else { "" };
+                format!("    {} -> {}{};", rule.candidate, rule.before, attributes)
+            }));
+            lines.push("}".to_string());
+        }
+        GraphFormat::Mermaid => {
+            lines.push("graph LR".to_string());
+            lines.extend(pages.iter().map(|page| format!("    p{}[{}]", page, page)));
+            lines.extend(
+                edges
+                    .iter()
+                    .map(|(rule, _)| format!("    p{} --> p{}", rule.candidate, rule.before)),
+            );
+            lines.extend(
+                edges
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, (_, violated))| *violated)
+                    .map(|(index, _)| format!("    linkStyle {} stroke:red", index)),
+            );
+        }
+    }
+    lines.join("\n")
+}
+
+// update_number is 1-based, with 0 exporting the full rule graph
+pub fn graph(input: &str, update_number: usize, format: GraphFormat) -> String {
+    let parsed_input = rules_and_updates(input);
+    let update = match update_number {
+        0 => None,
+        n => Some(
+            parsed_input
+                .updates
+                .get(n - 1)
+                .unwrap_or_else(|| panic!("No update number {}", n))
+                .as_slice(),
+        ),
+    };
+    render_graph(&parsed_input.rules, update, &format)
+}
+
+pub fn analyse(input: &str) -> String {
+    let parsed_input = rules_and_updates(input);
+    analyse_rules(&parsed_input).to_string()
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use advent_of_code_2024::read_test_file;
+    use advent_of_code_2024::{read_test_file, trimmed_lines};
 
     #[test]
     fn test_rules_and_updates() {
@@ -113,62 +446,205 @@ mod tests {
 
     #[test]
     fn test_process_update_part_a_valid_update() {
-        let actual = process_update_part_a(
-            vec![75, 47, 61, 53, 29],
-            &vec![
-                Rule {
-                    candidate: 75,
-                    before: 47,
-                },
-                Rule {
-                    candidate: 47,
-                    before: 53,
-                },
-                Rule {
-                    candidate: 61,
-                    before: 29,
-                },
-            ],
-        );
+        let rules = [
+            Rule {
+                candidate: 75,
+                before: 47,
+            },
+            Rule {
+                candidate: 47,
+                before: 53,
+            },
+            Rule {
+                candidate: 61,
+                before: 29,
+            },
+        ];
+        let actual = process_update_part_a(&[75, 47, 61, 53, 29], &RuleSet::from(&rules[..]));
         assert_eq!(actual, Some(61));
     }
 
     #[test]
     fn test_process_update_part_a_invalid_update() {
-        let actual = process_update_part_a(
-            vec![75, 47, 61, 53, 29],
-            &vec![
-                Rule {
-                    candidate: 75,
-                    before: 47,
-                },
-                Rule {
-                    candidate: 47,
-                    before: 53,
-                },
-                Rule {
-                    candidate: 61,
-                    before: 29,
-                },
-                Rule {
-                    candidate: 53,
-                    before: 61,
-                },
-            ],
-        );
+        let rules = [
+            Rule {
+                candidate: 75,
+                before: 47,
+            },
+            Rule {
+                candidate: 47,
+                before: 53,
+            },
+            Rule {
+                candidate: 61,
+                before: 29,
+            },
+            Rule {
+                candidate: 53,
+                before: 61,
+            },
+        ];
+        let actual = process_update_part_a(&[75, 47, 61, 53, 29], &RuleSet::from(&rules[..]));
         assert_eq!(actual, None);
     }
 
     #[test]
     fn test_process_update_part_b() {
-        let actual = process_update_part_b(
-            vec![47, 75, 97, 61, 53],
-            &vec![Rule {
-                candidate: 97,
-                before: 75,
-            }],
+        let rules = [Rule {
+            candidate: 97,
+            before: 75,
+        }];
+        let actual = process_update_part_b(&[47, 75, 97, 61, 53], &RuleSet::from(&rules[..]));
+        assert_eq!(actual, Some(vec![47, 97, 75, 61, 53]));
+    }
+
+    #[test]
+    fn test_process_update_part_b_ordered_update() {
+        let rules = [Rule {
+            candidate: 97,
+            before: 75,
+        }];
+        let actual = process_update_part_b(&[97, 75, 47], &RuleSet::from(&rules[..]));
+        assert_eq!(actual, None);
+    }
+
+    #[test]
+    fn test_sort_update_example() {
+        let input = read_test_file(5);
+        let parsed_input = rules_and_updates(&input);
+        let rule_set = RuleSet::from(&parsed_input.rules[..]);
+        assert_eq!(
+            rule_set.sort_update(&[97, 13, 75, 29, 47]),
+            Some(vec![97, 75, 47, 29, 13])
+        );
+        assert!(rule_set.must_precede(97, 13));
+        assert!(!rule_set.must_precede(13, 97));
+    }
+
+    #[test]
+    fn test_analyse_rules_example() {
+        let input = read_test_file(5);
+        let analysis = analyse_rules(&rules_and_updates(&input));
+        assert!(analysis.cycles.is_empty());
+        assert_eq!(analysis.redundant_rules.len(), 15);
+        assert!(analysis
+            .updates
+            .iter()
+            .all(|update| update.cycles.is_empty() && update.unconstrained_pairs.is_empty()));
+    }
+
+    #[test]
+    fn test_analyse_rules_cycles() {
+        let input = "1|2\n2|3\n3|1\n3|4\n5|5\n\n1,2,4\n1,3,2\n";
+        let analysis = analyse_rules(&rules_and_updates(input));
+        assert_eq!(analysis.cycles, vec![vec![1, 2, 3], vec![5]]);
+        assert!(analysis.updates[0].cycles.is_empty());
+        assert_eq!(analysis.updates[1].cycles, vec![vec![1, 2, 3]]);
+    }
+
+    #[test]
+    fn test_redundant_rules() {
+        let rules = [
+            Rule {
+                candidate: 1,
+                before: 2,
+            },
+            Rule {
+                candidate: 2,
+                before: 3,
+            },
+            Rule {
+                candidate: 1,
+                before: 3,
+            },
+        ];
+        let rule_set = RuleSet::from(&rules[..]);
+        assert_eq!(
+            rule_set.redundant_rules(&[1, 2, 3]),
+            vec![Rule {
+                candidate: 1,
+                before: 3
+            }]
+        );
+        assert!(rule_set.redundant_rules(&[1, 3]).is_empty());
+    }
+
+    #[test]
+    fn test_redundant_rules_with_cycle() {
+        let input = "1|2\n2|3\n3|1\n1|3\n3|4\n4|5\n1|5\n\n1,2\n";
+        let analysis = analyse_rules(&rules_and_updates(input));
+        assert_eq!(analysis.cycles, vec![vec![1, 2, 3]]);
+        assert_eq!(
+            analysis.redundant_rules,
+            vec![Rule {
+                candidate: 1,
+                before: 5
+            }]
         );
-        assert_eq!(actual, 75);
+    }
+
+    #[test]
+    fn test_unconstrained_pairs() {
+        let rules = [
+            Rule {
+                candidate: 1,
+                before: 2,
+            },
+            Rule {
+                candidate: 1,
+                before: 3,
+            },
+        ];
+        let rule_set = RuleSet::from(&rules[..]);
+        assert_eq!(rule_set.unconstrained_pairs(&[1, 2, 3]), vec![(2, 3)]);
+    }
+
+    #[test]
+    fn test_render_graph_dot() {
+        let rules = "97|75\n75|47\n47|13"
+            .lines()
+            .map(Rule::from)
+            .collect::<Vec<Rule>>();
+        let expected = r#"
+            digraph rules {
+                75;
+                97;
+                47;
+                97 -> 75 [color=red];
+                75 -> 47;
+            }
+        "#;
+        let actual = render_graph(&rules, Some(&[75, 97, 47]), &GraphFormat::Dot);
+        let expected_lines = trimmed_lines(expected);
+        assert_eq!(trimmed_lines(&actual), expected_lines);
+    }
+
+    #[test]
+    fn test_render_graph_mermaid() {
+        let rules = "97|75\n75|47"
+            .lines()
+            .map(Rule::from)
+            .collect::<Vec<Rule>>();
+        let expected = r#"
+            graph LR
+                p47[47]
+                p75[75]
+                p97[97]
+                p97 --> p75
+                p75 --> p47
+        "#;
+        let actual = render_graph(&rules, None, &GraphFormat::Mermaid);
+        let expected_lines = trimmed_lines(expected);
+        assert_eq!(trimmed_lines(&actual), expected_lines);
+    }
+
+    #[test]
+    fn test_graph_highlights_violations() {
+        let input = read_test_file(5);
+        let actual = graph(&input, 4, GraphFormat::Mermaid);
+        assert!(actual.contains("linkStyle"));
+        let actual = graph(&input, 1, GraphFormat::Dot);
+        assert!(!actual.contains("color=red"));
     }
 
     #[test]
@@ -1,66 +1,97 @@
-struct Direction {
-    x: i64,
-    y: i64,
-    symbol: char,
-}
-
-static DIRECTION_CHARS: [char; 4] = ['^', '>', 'v', '<'];
-
-static DIRECTIONS: [Direction; 4] = [
-    Direction {
-        x: 0,
-        y: -1,
-        symbol: '^',
-    },
-    Direction {
-        x: 1,
-        y: 0,
-        symbol: '>',
-    },
-    Direction {
-        x: 0,
-        y: 1,
-        symbol: 'v',
-    },
-    Direction {
-        x: -1,
-        y: 0,
-        symbol: '<',
-    },
-];
-
-#[derive(PartialEq, Clone, Copy)]
-enum Outcome {
-    LoopDetected,
-    ReachedBoundary,
-}
-
-fn build_grid(input: &str) -> Vec<Vec<char>> {
-    input.lines().map(|line| line.chars().collect()).collect()
-}
-
-fn find_start_position(grid: &Vec<Vec<char>>) -> (i64, i64) {
-    for (y, row) in grid.iter().enumerate() {
-        for (x, &cell) in row.iter().enumerate() {
-            if cell == '^' {
-                return (x as i64, y as i64);
-            }
+use advent_of_code_2024::grid::{
+    build_grid, in_bounds, state_index, State, TurnPolicy, DIRECTIONS,
+};
+use advent_of_code_2024::{to_i64, to_u32};
+use itertools::Itertools;
+use std::collections::HashSet;
+use std::fmt;
+
+pub enum BoundaryPolicy {
+    Exit,
+    Wrap,
+    // an edge cell marked with an uppercase letter leads to the other edge cell with the
+    // same letter; stepping off anywhere else leaves the grid
+    Portal,
+}
+
+impl From<&str> for BoundaryPolicy {
+    fn from(s: &str) -> Self {
+        match s {
+            "exit" => BoundaryPolicy::Exit,
+            "wrap" => BoundaryPolicy::Wrap,
+            "portal" => BoundaryPolicy::Portal,
+            _ => panic!("Invalid boundary policy: {}", s),
         }
     }
-    panic!("No start position found");
 }
 
-fn count_visited_cells(grid: &Vec<Vec<char>>) -> u32 {
-    grid.iter().fold(0, |acc, row| {
-        // check cell is not in DIRECTION_CHARS
-        acc + row
+struct Rules {
+    turn: TurnPolicy,
+    boundary: BoundaryPolicy,
+}
+
+static PUZZLE_RULES: Rules = Rules {
+    turn: TurnPolicy::Right,
+    boundary: BoundaryPolicy::Exit,
+};
+
+struct Outcome {
+    path: Vec<State>,
+    steps: u32,
+    loop_entry: Option<State>,
+}
+
+impl Outcome {
+    fn is_loop(&self) -> bool {
+        self.loop_entry.is_some()
+    }
+
+    fn visited_cells(&self) -> Vec<(i64, i64)> {
+        let mut seen = HashSet::new();
+        self.path
             .iter()
-            .filter(|&&cell| DIRECTION_CHARS.contains(&cell))
-            .count() as u32
-    })
+            .map(|state| state.position)
+            .filter(|&position| seen.insert(position))
+            .collect()
+    }
+}
+
+struct BitSet {
+    words: Vec<u64>,
 }
 
-fn _print_grid(grid: &Vec<Vec<char>>) {
+impl BitSet {
+    fn new(size: usize) -> Self {
+        BitSet {
+            words: vec![0; size.div_ceil(64)],
+        }
+    }
+
+    // returns false if the bit was already set
+    fn insert(&mut self, index: usize) -> bool {
+        let (word, bit) = (index / 64, 1 << (index % 64));
+        let was_set = self.words[word] & bit != 0;
+        self.words[word] |= bit;
+        !was_set
+    }
+}
+
+fn find_guards(grid: &[Vec<char>]) -> Vec<State> {
+    let mut guards = Vec::new();
+    for (y, row) in grid.iter().enumerate() {
+        for (x, &cell) in row.iter().enumerate() {
+            if let Some(direction) = DIRECTIONS.iter().position(|d| d.symbol == cell) {
+                guards.push(State {
+                    position: (x as i64, y as i64),
+                    direction,
+                });
+            }
+        }
+    }
+    guards
+}
+
+fn _print_grid(grid: &[Vec<char>]) {
     for row in grid {
         for cell in row {
             print!("{}", cell);
@@ -70,90 +101,585 @@ fn _print_grid(grid: &Vec<Vec<char>>) {
     println!("\n\n");
 }
 
-fn process(grid: &mut Vec<Vec<char>>) -> Outcome {
-    let mut direction_iter = DIRECTIONS.iter().cycle();
+fn is_edge(grid: &[Vec<char>], position: (i64, i64)) -> bool {
+    position.0 == 0
+        || position.0 == grid[0].len() as i64 - 1
+        || position.1 == 0
+        || position.1 == grid.len() as i64 - 1
+}
 
-    let mut position = find_start_position(&grid);
-    let mut direction = direction_iter.next().unwrap();
+// the edge cell paired with the portal at `position`, or None if it is not a portal
+fn portal_exit(grid: &[Vec<char>], position: (i64, i64)) -> Option<(i64, i64)> {
+    let portal = grid[position.1 as usize][position.0 as usize];
+    if !portal.is_ascii_uppercase() {
+        return None;
+    }
+    let pairs = (0..grid.len() as i64)
+        .cartesian_product(0..grid[0].len() as i64)
+        .map(|(y, x)| (x, y))
+        .filter(|&cell| cell != position && is_edge(grid, cell))
+        .filter(|&(x, y)| grid[y as usize][x as usize] == portal)
+        .collect::<Vec<(i64, i64)>>();
+    match pairs[..] {
+        [pair] => Some(pair),
+        _ => panic!("Portal {} must have exactly one paired edge cell", portal),
+    }
+}
 
-    loop {
-        let next_position: (i64, i64) = (position.0 + direction.x, position.1 + direction.y);
-        let next_position_is_out_of_bounds = next_position.0 < 0
-            || next_position.0 >= grid[0].len() as i64
-            || next_position.1 < 0
-            || next_position.1 >= grid.len() as i64;
-
-        if next_position_is_out_of_bounds {
-            return Outcome::ReachedBoundary;
+fn next_position(grid: &[Vec<char>], state: &State, rules: &Rules) -> Option<(i64, i64)> {
+    let direction = &DIRECTIONS[state.direction];
+    let next_position = (
+        state.position.0 + direction.x,
+        state.position.1 + direction.y,
+    );
+    match rules.boundary {
+        _ if in_bounds(grid, next_position) => Some(next_position),
+        BoundaryPolicy::Exit => None,
+        BoundaryPolicy::Wrap => Some((
+            next_position.0.rem_euclid(grid[0].len() as i64),
+            next_position.1.rem_euclid(grid.len() as i64),
+        )),
+        BoundaryPolicy::Portal => portal_exit(grid, state.position),
+    }
+}
+
+#[derive(PartialEq, Debug)]
+struct Meeting {
+    tick: u32,
+    position: (i64, i64),
+}
+
+struct Guard {
+    visited: BitSet,
+    path: Vec<State>,
+    steps: u32,
+    loop_entry: Option<State>,
+    finished: bool,
+}
+
+impl Guard {
+    fn new(grid: &[Vec<char>], start: State) -> Self {
+        let mut visited = BitSet::new(grid.len() * grid[0].len() * DIRECTIONS.len());
+        visited.insert(state_index(grid, &start));
+        Guard {
+            visited,
+            path: vec![start],
+            steps: 0,
+            loop_entry: None,
+            finished: false,
         }
+    }
 
-        let next_position_is_obstacle =
-            grid[next_position.1 as usize][next_position.0 as usize] == '#';
+    fn state(&self) -> State {
+        *self.path.last().unwrap()
+    }
+
+    fn step(&mut self, grid: &[Vec<char>], rules: &Rules) {
+        let state = self.state();
+        let Some(next_position) = next_position(grid, &state, rules) else {
+            self.finished = true;
+            return;
+        };
 
-        let loop_detected =
-            grid[next_position.1 as usize][next_position.0 as usize] == direction.symbol;
+        let next_state = if grid[next_position.1 as usize][next_position.0 as usize] == '#' {
+            State {
+                position: state.position,
+                direction: rules.turn.turn(state.direction),
+            }
+        } else {
+            self.steps += 1;
+            State {
+                position: next_position,
+                direction: state.direction,
+            }
+        };
 
-        if next_position_is_obstacle {
-            direction = direction_iter.next().unwrap();
-        } else if loop_detected {
-            return Outcome::LoopDetected;
+        if self.visited.insert(state_index(grid, &next_state)) {
+            self.path.push(next_state);
         } else {
-            position = next_position;
-            grid[position.1 as usize][next_position.0 as usize] = direction.symbol;
+            self.loop_entry = Some(next_state);
+            self.finished = true;
+        }
+    }
+
+    fn into_outcome(self) -> Outcome {
+        Outcome {
+            path: self.path,
+            steps: self.steps,
+            loop_entry: self.loop_entry,
         }
     }
 }
 
+fn simulate(grid: &[Vec<char>], start: State, rules: &Rules) -> Outcome {
+    let mut guard = Guard::new(grid, start);
+    while !guard.finished {
+        guard.step(grid, rules);
+    }
+    guard.into_outcome()
+}
+
+// guards step in lockstep, one move or turn each per tick, and do not block each other;
+// returns each guard's outcome along with every tick and cell where guards met
+fn simulate_guards(
+    grid: &[Vec<char>],
+    starts: &[State],
+    rules: &Rules,
+) -> (Vec<Outcome>, Vec<Meeting>) {
+    let mut guards = starts
+        .iter()
+        .map(|&start| Guard::new(grid, start))
+        .collect::<Vec<Guard>>();
+    let mut meetings = Vec::new();
+    let mut tick = 0;
+
+    while guards.iter().any(|guard| !guard.finished) {
+        guards
+            .iter_mut()
+            .filter(|guard| !guard.finished)
+            .for_each(|guard| guard.step(grid, rules));
+        tick += 1;
+
+        let positions = guards
+            .iter()
+            .filter(|guard| !guard.finished)
+            .map(|guard| guard.state().position)
+            .counts();
+        meetings.extend(
+            positions
+                .into_iter()
+                .filter(|&(_, count)| count > 1)
+                .map(|(position, _)| position)
+                .sorted()
+                .map(|position| Meeting { tick, position }),
+        );
+    }
+
+    let outcomes = guards.into_iter().map(Guard::into_outcome).collect();
+    (outcomes, meetings)
+}
+
+fn start_state(grid: &[Vec<char>]) -> State {
+    *find_guards(grid).first().expect("No start position found")
+}
+
 pub fn part_a(input: &str) -> u32 {
-    let mut grid = build_grid(input);
-    let outcome = process(&mut grid);
-    match outcome {
-        Outcome::LoopDetected => panic!("Loop detected"),
-        Outcome::ReachedBoundary => count_visited_cells(&grid),
+    let grid = build_grid(input);
+    let outcome = simulate(&grid, start_state(&grid), &PUZZLE_RULES);
+    if outcome.is_loop() {
+        panic!("Loop detected after {} steps", outcome.steps);
     }
+    outcome.visited_cells().len() as u32
 }
 
-pub fn part_b(input: &str) -> u32 {
-    let base_grid = build_grid(input);
-    let mut part_a_grid = base_grid.clone();
+// for every state, the cell the guard stops at in front of the next obstacle, or None
+// if it walks off the grid first
+struct JumpTable {
+    stops: Vec<Option<(i64, i64)>>,
+}
+
+impl JumpTable {
+    fn new(grid: &[Vec<char>]) -> Self {
+        let height = grid.len() as i64;
+        let width = grid[0].len() as i64;
+        let mut stops = vec![None; grid.len() * grid[0].len() * DIRECTIONS.len()];
+
+        for (d, direction) in DIRECTIONS.iter().enumerate() {
+            // visit cells so that the neighbour ahead is always filled in first
+            let ys = (0..height)
+                .map(|y| if direction.y > 0 { height - 1 - y } else { y })
+                .collect::<Vec<i64>>();
+            let xs = (0..width)
+                .map(|x| if direction.x > 0 { width - 1 - x } else { x })
+                .collect::<Vec<i64>>();
+            for &y in ys.iter() {
+                for &x in xs.iter() {
+                    let state = State {
+                        position: (x, y),
+                        direction: d,
+                    };
+                    let next_position = (x + direction.x, y + direction.y);
+                    stops[state_index(grid, &state)] = if !in_bounds(grid, next_position) {
+                        None
+                    } else if grid[next_position.1 as usize][next_position.0 as usize] == '#' {
+                        Some((x, y))
+                    } else {
+                        let next_state = State {
+                            position: next_position,
+                            direction: d,
+                        };
+                        stops[state_index(grid, &next_state)]
+                    };
+                }
+            }
+        }
+        JumpTable { stops }
+    }
+
+    fn stop(&self, grid: &[Vec<char>], state: &State) -> Option<(i64, i64)> {
+        self.stops[state_index(grid, state)]
+    }
+}
+
+// distance ahead of the state to the given cell, if it lies on the guard's line of travel
+fn distance_ahead(state: &State, cell: (i64, i64)) -> Option<i64> {
+    let direction = &DIRECTIONS[state.direction];
+    let dx = cell.0 - state.position.0;
+    let dy = cell.1 - state.position.1;
+    let distance = dx * direction.x + dy * direction.y;
+    let on_line = dx == distance * direction.x && dy == distance * direction.y;
+    if on_line && distance > 0 {
+        Some(distance)
+    } else {
+        None
+    }
+}
 
-    let outcome = process(&mut part_a_grid);
-    match outcome {
-        Outcome::LoopDetected => panic!("Loop detected in part a grid"),
-        Outcome::ReachedBoundary => count_visited_cells(&base_grid),
+fn loops_with_obstacle(
+    grid: &[Vec<char>],
+    jump_table: &JumpTable,
+    start: State,
+    obstacle: (i64, i64),
+) -> bool {
+    let mut visited = BitSet::new(grid.len() * grid[0].len() * DIRECTIONS.len());
+    let mut state = start;
+
+    loop {
+        let direction = &DIRECTIONS[state.direction];
+        let obstacle_distance = jump_table
+            .stop(grid, &state)
+            .map(|position| distance_ahead(&state, position).unwrap_or(0) + 1);
+        let blocked_distance = match (obstacle_distance, distance_ahead(&state, obstacle)) {
+            (Some(a), Some(b)) => Some(a.min(b)),
+            (a, b) => a.or(b),
+        };
+        let stop = blocked_distance.map(|distance| {
+            (
+                state.position.0 + direction.x * (distance - 1),
+                state.position.1 + direction.y * (distance - 1),
+            )
+        });
+
+        match stop {
+            None => return false,
+            Some(position) => {
+                state = State {
+                    position,
+                    direction: (state.direction + 1) % DIRECTIONS.len(),
+                };
+                if !visited.insert(state_index(grid, &state)) {
+                    return true;
+                }
+            }
+        }
+    }
+}
+
+#[derive(PartialEq, Debug)]
+enum Event {
+    Start,
+    Move,
+    Turn,
+    Revisit,
+    LoopClosed,
+    Exit,
+}
+
+impl From<&str> for Event {
+    fn from(s: &str) -> Self {
+        match s {
+            "start" => Event::Start,
+            "move" => Event::Move,
+            "turn" => Event::Turn,
+            "revisit" => Event::Revisit,
+            "loop-closed" => Event::LoopClosed,
+            "exit" => Event::Exit,
+            _ => panic!("Invalid event: {}", s),
+        }
+    }
+}
+
+impl fmt::Display for Event {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name = match self {
+            Event::Start => "start",
+            Event::Move => "move",
+            Event::Turn => "turn",
+            Event::Revisit => "revisit",
+            Event::LoopClosed => "loop-closed",
+            Event::Exit => "exit",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+// `step` is the number of moves made so far, so a turn in place shares the step of the
+// move before it
+#[derive(PartialEq, Debug)]
+struct RouteEntry {
+    step: u32,
+    state: State,
+    event: Event,
+}
+
+static ROUTE_FIELDS: [&str; 5] = ["step", "x", "y", "direction", "event"];
+
+impl RouteEntry {
+    fn to_csv(&self) -> String {
+        format!(
+            "{},{},{},{},{}",
+            self.step,
+            self.state.position.0,
+            self.state.position.1,
+            DIRECTIONS[self.state.direction].symbol,
+            self.event
+        )
+    }
+
+    fn to_json(&self) -> String {
+        format!(
+            "{{\"step\": {}, \"x\": {}, \"y\": {}, \"direction\": \"{}\", \"event\": \"{}\"}}",
+            self.step,
+            self.state.position.0,
+            self.state.position.1,
+            DIRECTIONS[self.state.direction].symbol,
+            self.event
+        )
+    }
+
+    // fields in ROUTE_FIELDS order
+    fn from_fields(fields: &[&str]) -> Self {
+        if fields.len() != ROUTE_FIELDS.len() {
+            panic!(
+                "Invalid route entry [{}], expected the fields {}",
+                fields.join(","),
+                ROUTE_FIELDS.join(",")
+            );
+        }
+        let symbol = fields[3].chars().next().unwrap_or(' ');
+        RouteEntry {
+            step: to_u32(fields[0]),
+            state: State {
+                position: (to_i64(fields[1]), to_i64(fields[2])),
+                direction: DIRECTIONS
+                    .iter()
+                    .position(|d| d.symbol == symbol)
+                    .unwrap_or_else(|| panic!("Invalid direction: {}", fields[3])),
+            },
+            event: Event::from(fields[4]),
+        }
+    }
+}
+
+fn route(outcome: &Outcome) -> Vec<RouteEntry> {
+    let mut seen = HashSet::new();
+    let mut entries = Vec::new();
+    let mut previous: Option<State> = None;
+    let mut step = 0;
+
+    for &state in outcome.path.iter() {
+        let event = match previous {
+            None => Event::Start,
+            Some(previous) if previous.position == state.position => Event::Turn,
+            Some(_) if seen.contains(&state.position) => Event::Revisit,
+            Some(_) => Event::Move,
+        };
+        if matches!(event, Event::Move | Event::Revisit) {
+            step += 1;
+        }
+        seen.insert(state.position);
+        entries.push(RouteEntry { step, state, event });
+        previous = Some(state);
+    }
+
+    let (state, event) = match outcome.loop_entry {
+        Some(entry) => (entry, Event::LoopClosed),
+        None => (previous.unwrap(), Event::Exit),
     };
+    entries.push(RouteEntry {
+        step: outcome.steps,
+        state,
+        event,
+    });
+    entries
+}
 
-    let grid_variants = build_grid_variants(base_grid, &part_a_grid);
+// the values of a flat JSON object's fields in ROUTE_FIELDS order, read by key so any
+// spacing or field order is accepted. a missing field is left out, which from_fields
+// reports
+fn json_route_fields(object: &str) -> Vec<&str> {
+    let pairs = object
+        .split(',')
+        .filter_map(|pair| pair.split_once(':'))
+        .map(|(key, value)| (key.trim().trim_matches('"'), value.trim().trim_matches('"')))
+        .collect::<Vec<(&str, &str)>>();
+    ROUTE_FIELDS
+        .iter()
+        .filter_map(|field| pairs.iter().find(|(key, _)| key == field).map(|p| p.1))
+        .collect()
+}
 
-    let outcomes = grid_variants
-        .into_iter()
-        .map(|mut g| process(&mut g))
-        .collect::<Vec<Outcome>>();
+fn parse_route(saved: &str) -> Vec<RouteEntry> {
+    let route_export = "replay needs a route saved by `--part a export`";
+    if saved.trim_start().starts_with('[') {
+        saved
+            .split('{')
+            .skip(1)
+            .map(|object| {
+                let object = object
+                    .split_once('}')
+                    .unwrap_or_else(|| panic!("Unterminated JSON object, {}", route_export))
+                    .0;
+                if !object.contains("\"event\"") {
+                    panic!("Saved JSON has no route events, {}", route_export);
+                }
+                RouteEntry::from_fields(&json_route_fields(object))
+            })
+            .collect()
+    } else {
+        let mut lines = saved.lines();
+        let header = lines.next().unwrap_or_default().trim();
+        if header != ROUTE_FIELDS.join(",") {
+            panic!("Unexpected CSV header [{}], {}", header, route_export);
+        }
+        lines
+            .map(|line| RouteEntry::from_fields(&line.split(',').collect::<Vec<&str>>()))
+            .collect()
+    }
+}
 
-    outcomes
-        .into_iter()
-        .filter(|&outcome| outcome == Outcome::LoopDetected)
-        .count() as u32
-}
-
-fn build_grid_variants(
-    base_grid: Vec<Vec<char>>,
-    part_a_grid: &Vec<Vec<char>>,
-) -> Vec<Vec<Vec<char>>> {
-    let mut grid_variants = Vec::new();
-
-    for i in 0..base_grid.len() {
-        for j in 0..base_grid[i].len() {
-            let visited_in_initial_grid = DIRECTION_CHARS.contains(&part_a_grid[i][j]);
-            let not_visited_in_base_grid = base_grid[i][j] != '^';
-            if visited_in_initial_grid && not_visited_in_base_grid {
-                let mut new_grid = base_grid.clone();
-                new_grid[i][j] = '#';
-                grid_variants.push(new_grid);
+fn to_json_array(items: &[String]) -> String {
+    if items.is_empty() {
+        return "[]".to_string();
+    }
+    format!("[\n  {}\n]", items.join(",\n  "))
+}
+
+pub fn export(input: &str, part: char, format: &str) -> String {
+    let grid = build_grid(input);
+    let outcome = simulate(&grid, start_state(&grid), &PUZZLE_RULES);
+    match (part, format) {
+        ('a', "csv") => [ROUTE_FIELDS.join(",")]
+            .into_iter()
+            .chain(route(&outcome).iter().map(RouteEntry::to_csv))
+            .join("\n"),
+        ('a', "json") => to_json_array(
+            &route(&outcome)
+                .iter()
+                .map(RouteEntry::to_json)
+                .collect_vec(),
+        ),
+        ('b', "csv") => ["x,y".to_string()]
+            .into_iter()
+            .chain(
+                loop_obstacles(&grid, &outcome)
+                    .iter()
+                    .map(|(x, y)| format!("{},{}", x, y)),
+            )
+            .join("\n"),
+        ('b', "json") => to_json_array(
+            &loop_obstacles(&grid, &outcome)
+                .iter()
+                .map(|(x, y)| format!("{{\"x\": {}, \"y\": {}}}", x, y))
+                .collect_vec(),
+        ),
+        _ => panic!("Invalid export format: {}", format),
+    }
+}
+
+// renders the grid with the route up to and including the given step, visited cells
+// marked X and the guard shown by its heading
+pub fn replay(input: &str, saved: &str, step: u32) -> String {
+    let mut grid = build_grid(input);
+    for row in grid.iter_mut() {
+        for cell in row.iter_mut() {
+            if *cell != '#' {
+                *cell = '.';
             }
         }
     }
-    grid_variants
+
+    let entries = parse_route(saved)
+        .into_iter()
+        .take_while(|entry| entry.step <= step)
+        .collect::<Vec<RouteEntry>>();
+    for entry in entries.iter() {
+        grid[entry.state.position.1 as usize][entry.state.position.0 as usize] = 'X';
+    }
+    if let Some(entry) = entries.last() {
+        grid[entry.state.position.1 as usize][entry.state.position.0 as usize] =
+            DIRECTIONS[entry.state.direction].symbol;
+    }
+
+    grid.iter()
+        .map(|row| row.iter().collect::<String>())
+        .join("\n")
+}
+
+pub fn part_b(input: &str) -> u32 {
+    let grid = build_grid(input);
+    let start = start_state(&grid);
+
+    let outcome = simulate(&grid, start, &PUZZLE_RULES);
+    if outcome.is_loop() {
+        panic!("Loop detected in part a grid");
+    }
+
+    loop_obstacles(&grid, &outcome).len() as u32
+}
+
+fn loop_obstacles(grid: &[Vec<char>], outcome: &Outcome) -> Vec<(i64, i64)> {
+    let jump_table = JumpTable::new(grid);
+    let mut tried = HashSet::from([outcome.path[0].position]);
+
+    // an obstacle only changes the route from the first time the guard would reach it,
+    // so each candidate is checked from the state just before that point
+    outcome
+        .path
+        .windows(2)
+        .filter(|states| states[0].position != states[1].position)
+        .filter(|states| tried.insert(states[1].position))
+        .filter(|states| loops_with_obstacle(grid, &jump_table, states[0], states[1].position))
+        .map(|states| states[1].position)
+        .collect()
+}
+
+pub fn simulate_variant(input: &str, turn: TurnPolicy, boundary: BoundaryPolicy) -> String {
+    let grid = build_grid(input);
+    let rules = Rules { turn, boundary };
+    let starts = find_guards(&grid);
+    let (outcomes, meetings) = simulate_guards(&grid, &starts, &rules);
+
+    let mut lines = outcomes
+        .iter()
+        .enumerate()
+        .map(|(index, outcome)| {
+            let start = outcome.path[0];
+            let ending = match outcome.loop_entry {
+                Some(entry) => format!(
+                    "loop entered at {:?} heading {}",
+                    entry.position, DIRECTIONS[entry.direction].symbol
+                ),
+                None => "left the grid".to_string(),
+            };
+            format!(
+                "Guard {} from {:?} heading {}: {} after {} steps, {} cells visited",
+                index + 1,
+                start.position,
+                DIRECTIONS[start.direction].symbol,
+                ending,
+                outcome.steps,
+                outcome.visited_cells().len()
+            )
+        })
+        .collect::<Vec<String>>();
+    lines.extend(meetings.iter().map(|meeting| {
+        format!(
+            "Guards met at {:?} on tick {}",
+            meeting.position, meeting.tick
+        )
+    }));
+    lines.join("\n")
 }
 
 #[cfg(test)]
@@ -161,6 +687,278 @@ mod tests {
     use super::*;
     use advent_of_code_2024::read_test_file;
 
+    #[test]
+    fn test_simulate_reaches_boundary() {
+        let grid = build_grid(&read_test_file(6));
+        let outcome = simulate(&grid, start_state(&grid), &PUZZLE_RULES);
+        assert!(!outcome.is_loop());
+        assert_eq!(outcome.visited_cells().len(), 41);
+        assert_eq!(outcome.path[0].position, (4, 6));
+    }
+
+    #[test]
+    fn test_simulate_detects_loop() {
+        let input = r#"
+            .#....
+            .....#
+            #.....
+            .^..#.
+        "#
+        .replace(" ", "");
+        let grid = build_grid(input.trim());
+        let outcome = simulate(&grid, start_state(&grid), &PUZZLE_RULES);
+        assert!(outcome.is_loop());
+        assert_eq!(
+            outcome.loop_entry,
+            Some(State {
+                position: (1, 2),
+                direction: 0
+            })
+        );
+    }
+
+    #[test]
+    fn test_find_guards() {
+        let grid = build_grid("^.>\n...\n<.v");
+        let guards = find_guards(&grid);
+        let directions = guards.iter().map(|g| g.direction).collect::<Vec<usize>>();
+        assert_eq!(directions, vec![0, 1, 3, 2]);
+        assert_eq!(guards[3].position, (2, 2));
+    }
+
+    #[test]
+    fn test_simulate_turn_left() {
+        let input = r#"
+            ...#..
+            ......
+            ..#...
+            ...^.#
+        "#
+        .replace(" ", "");
+        let grid = build_grid(input.trim());
+        let rules = Rules {
+            turn: TurnPolicy::Left,
+            boundary: BoundaryPolicy::Exit,
+        };
+        let outcome = simulate(&grid, start_state(&grid), &rules);
+        assert!(!outcome.is_loop());
+        let last = outcome.path.last().unwrap();
+        assert_eq!(last.position, (0, 1));
+        assert_eq!(last.direction, 3);
+    }
+
+    #[test]
+    fn test_simulate_reverse_and_wrap() {
+        let grid = build_grid("#.^.");
+        let reverse = Rules {
+            turn: TurnPolicy::Reverse,
+            boundary: BoundaryPolicy::Exit,
+        };
+        let outcome = simulate(&grid, start_state(&grid), &reverse);
+        assert!(!outcome.is_loop());
+
+        let wrap = Rules {
+            turn: TurnPolicy::Right,
+            boundary: BoundaryPolicy::Wrap,
+        };
+        let outcome = simulate(&grid, start_state(&grid), &wrap);
+        assert!(outcome.is_loop());
+        assert_eq!(outcome.steps, 1);
+    }
+
+    #[test]
+    fn test_simulate_portal() {
+        let input = r#"
+            .A..
+            .^..
+            ....
+            ....
+            A...
+        "#
+        .replace(" ", "");
+        let grid = build_grid(input.trim());
+        let portal = Rules {
+            turn: TurnPolicy::Right,
+            boundary: BoundaryPolicy::Portal,
+        };
+        let outcome = simulate(&grid, start_state(&grid), &portal);
+        assert!(!outcome.is_loop());
+        assert_eq!(outcome.steps, 6);
+        assert_eq!(outcome.visited_cells().len(), 7);
+        assert_eq!(outcome.path.last().unwrap().position, (0, 0));
+    }
+
+    #[test]
+    #[should_panic(expected = "Portal A must have exactly one paired edge cell")]
+    fn test_simulate_unpaired_portal() {
+        let grid = build_grid(".A.\n.^.\n...");
+        let portal = Rules {
+            turn: TurnPolicy::Right,
+            boundary: BoundaryPolicy::Portal,
+        };
+        simulate(&grid, start_state(&grid), &portal);
+    }
+
+    #[test]
+    fn test_simulate_guards_in_lockstep() {
+        let grid = build_grid(">...<");
+        let (outcomes, meetings) = simulate_guards(&grid, &find_guards(&grid), &PUZZLE_RULES);
+        assert_eq!(outcomes.len(), 2);
+        assert!(outcomes.iter().all(|outcome| !outcome.is_loop()));
+        assert_eq!(
+            meetings,
+            vec![Meeting {
+                tick: 2,
+                position: (2, 0)
+            }]
+        );
+    }
+
+    #[test]
+    fn test_jump_table() {
+        let grid = build_grid(&read_test_file(6));
+        let jump_table = JumpTable::new(&grid);
+        let start = start_state(&grid);
+        assert_eq!(jump_table.stop(&grid, &start), Some((4, 1)));
+        let heading_left = State {
+            position: (4, 6),
+            direction: 3,
+        };
+        assert_eq!(jump_table.stop(&grid, &heading_left), Some((2, 6)));
+        let heading_down = State {
+            position: (4, 6),
+            direction: 2,
+        };
+        assert_eq!(jump_table.stop(&grid, &heading_down), None);
+    }
+
+    #[test]
+    fn test_loops_with_obstacle() {
+        let grid = build_grid(&read_test_file(6));
+        let jump_table = JumpTable::new(&grid);
+        let start = start_state(&grid);
+        assert!(loops_with_obstacle(&grid, &jump_table, start, (3, 6)));
+        assert!(!loops_with_obstacle(&grid, &jump_table, start, (4, 5)));
+    }
+
+    #[test]
+    fn test_route_events() {
+        let grid = build_grid("..#.\n....\n....\n..^.");
+        let outcome = simulate(&grid, start_state(&grid), &PUZZLE_RULES);
+        let entries = route(&outcome);
+        let steps = entries.iter().map(|entry| entry.step).collect_vec();
+        assert_eq!(steps, vec![0, 1, 2, 2, 3, 3]);
+        assert_eq!(entries.last().unwrap().step, outcome.steps);
+        let events = entries
+            .into_iter()
+            .map(|entry| entry.event)
+            .collect::<Vec<Event>>();
+        assert_eq!(
+            events,
+            vec![
+                Event::Start,
+                Event::Move,
+                Event::Move,
+                Event::Turn,
+                Event::Move,
+                Event::Exit
+            ]
+        );
+    }
+
+    #[test]
+    fn test_route_loop_closed() {
+        let input = r#"
+            .#....
+            .....#
+            #.....
+            .^..#.
+        "#
+        .replace(" ", "");
+        let grid = build_grid(input.trim());
+        let outcome = simulate(&grid, start_state(&grid), &PUZZLE_RULES);
+        let entries = route(&outcome);
+        let last = entries.last().unwrap();
+        assert_eq!(last.event, Event::LoopClosed);
+        assert_eq!(last.state.position, (1, 2));
+        assert!(entries.iter().any(|entry| entry.event == Event::Revisit));
+    }
+
+    #[test]
+    fn test_parse_route_round_trip() {
+        let input = read_test_file(6);
+        let grid = build_grid(&input);
+        let entries = route(&simulate(&grid, start_state(&grid), &PUZZLE_RULES));
+        assert_eq!(parse_route(&export(&input, 'a', "csv")), entries);
+        assert_eq!(parse_route(&export(&input, 'a', "json")), entries);
+    }
+
+    #[test]
+    fn test_parse_route_reformatted_json() {
+        let saved = r#"[{"x":4,"step":0,"y":6,
+            "direction":"^","event":"start"}]"#;
+        assert_eq!(
+            parse_route(saved),
+            vec![RouteEntry {
+                step: 0,
+                state: State {
+                    position: (4, 6),
+                    direction: 0
+                },
+                event: Event::Start
+            }]
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "replay needs a route saved by `--part a export`")]
+    fn test_parse_route_rejects_obstacle_csv() {
+        parse_route(&export(&read_test_file(6), 'b', "csv"));
+    }
+
+    #[test]
+    #[should_panic(expected = "replay needs a route saved by `--part a export`")]
+    fn test_parse_route_rejects_obstacle_json() {
+        parse_route(&export(&read_test_file(6), 'b', "json"));
+    }
+
+    #[test]
+    #[should_panic(expected = "expected the fields step,x,y,direction,event")]
+    fn test_parse_route_short_entry() {
+        parse_route("step,x,y,direction,event\n0,4,6");
+    }
+
+    #[test]
+    fn test_export_loop_obstacles() {
+        let input = read_test_file(6);
+        let expected = "x,y\n3,6\n6,7\n3,8\n1,8\n7,9\n7,7";
+        let actual = export(&input, 'b', "csv");
+        assert_eq!(
+            actual.lines().sorted().collect_vec(),
+            expected.lines().sorted().collect_vec()
+        );
+    }
+
+    #[test]
+    fn test_replay() {
+        let input = read_test_file(6);
+        let saved = export(&input, 'a', "csv");
+        let expected = r#"
+            ....#.....
+            ....>....#
+            ....X.....
+            ..#.X.....
+            ....X..#..
+            ....X.....
+            .#..X.....
+            ........#.
+            #.........
+            ......#...
+        "#
+        .replace(" ", "");
+        assert_eq!(replay(&input, &saved, 5), expected.trim());
+    }
+
     #[test]
     fn test_part_a() {
         let input = read_test_file(6);
@@ -1,75 +1,561 @@
-use advent_of_code_2024::to_i64;
-use itertools::Itertools;
+use num_bigint::BigInt;
+use num_traits::{
+    CheckedAdd, CheckedDiv, CheckedMul, CheckedSub, FromPrimitive, One, ToPrimitive, Zero,
+};
+use std::collections::HashMap;
+use std::fmt::Display;
+use std::hash::Hash;
+use std::str::FromStr;
 
-struct Line {
-    target: i64,
-    test_values: Vec<i64>,
+// numeric backend for evaluating equations; every operation is checked so that overflow
+// can be detected rather than wrapping or panicking
+trait Number:
+    Clone
+    + Eq
+    + Ord
+    + Hash
+    + Display
+    + FromStr
+    + Zero
+    + One
+    + FromPrimitive
+    + ToPrimitive
+    + CheckedAdd
+    + CheckedSub
+    + CheckedMul
+    + CheckedDiv
+    + 'static
+{
 }
 
-fn parse_line(line: &str) -> Line {
+impl<T> Number for T where
+    T: Clone
+        + Eq
+        + Ord
+        + Hash
+        + Display
+        + FromStr
+        + Zero
+        + One
+        + FromPrimitive
+        + ToPrimitive
+        + CheckedAdd
+        + CheckedSub
+        + CheckedMul
+        + CheckedDiv
+        + 'static
+{
+}
+
+fn checked_rem<N: Number>(left: &N, right: &N) -> Option<N> {
+    let quotient = left.checked_div(right)?;
+    left.checked_sub(&quotient.checked_mul(right)?)
+}
+
+fn to_number<N: Number>(s: &str) -> N {
+    s.trim()
+        .parse::<N>()
+        .unwrap_or_else(|_| panic!("Invalid number: {}", s))
+}
+
+struct Line<N> {
+    target: N,
+    test_values: Vec<N>,
+}
+
+fn parse_line<N: Number>(line: &str) -> Line<N> {
     let parts = line.split_once(": ").unwrap();
-    let target = parts.0.parse::<i64>().unwrap();
-    let test_values = parts.1.split(" ").map(to_i64).collect();
+    let target = to_number(parts.0);
+    let test_values = parts.1.split(" ").map(to_number).collect();
     Line {
         target,
         test_values,
     }
 }
 
-fn process_line(line: &Line, operators: &Vec<char>) -> bool {
-    // Generate all possible operator combinations for the length of test_values - 1
-    let operator_combinations = std::iter::repeat(operators)
-        .take(line.test_values.len() - 1)
-        .multi_cartesian_product();
+// what the solver does when an operator has no result for its operands, either because
+// the result overflows or because the operator is undefined there (e.g. inexact division)
+pub enum Overflow {
+    Prune,
+    Panic,
+}
 
-    for combination in operator_combinations {
-        let mut result = line.test_values[0];
+enum Inverse<N> {
+    Operand(N),
+    Impossible,
+    Unsupported,
+}
 
-        for (i, &op) in combination.iter().enumerate() {
-            match op {
-                '+' => result += line.test_values[i + 1],
-                '*' => result *= line.test_values[i + 1],
-                '|' => result = to_i64(&format!("{}{}", result, line.test_values[i + 1])),
-                _ => panic!("Invalid operator: {}", op),
-            }
+impl<N> From<Option<N>> for Inverse<N> {
+    fn from(left: Option<N>) -> Self {
+        match left {
+            Some(left) => Inverse::Operand(left),
+            None => Inverse::Impossible,
+        }
+    }
+}
+
+trait Operator<N> {
+    fn symbol(&self) -> &str;
+
+    fn apply(&self, left: &N, right: &N) -> Option<N>;
+
+    // the left operand that gives `result` when combined with `right`, for solving backwards
+    fn inverse(&self, _result: &N, _right: &N) -> Inverse<N> {
+        Inverse::Unsupported
+    }
+
+    fn overflow(&self) -> Overflow {
+        Overflow::Prune
+    }
+
+    // whether the result is never negative when neither operand is
+    fn keeps_non_negative(&self) -> bool {
+        false
+    }
+}
+
+struct Add;
+struct Multiply;
+struct Concatenate;
+struct Subtract;
+struct Divide;
+struct Power;
+struct Max;
+
+impl<N: Number> Operator<N> for Add {
+    fn symbol(&self) -> &str {
+        "+"
+    }
+
+    fn apply(&self, left: &N, right: &N) -> Option<N> {
+        left.checked_add(right)
+    }
+
+    fn inverse(&self, result: &N, right: &N) -> Inverse<N> {
+        result.checked_sub(right).into()
+    }
+
+    fn keeps_non_negative(&self) -> bool {
+        true
+    }
+}
+
+impl<N: Number> Operator<N> for Multiply {
+    fn symbol(&self) -> &str {
+        "*"
+    }
+
+    fn apply(&self, left: &N, right: &N) -> Option<N> {
+        left.checked_mul(right)
+    }
+
+    fn inverse(&self, result: &N, right: &N) -> Inverse<N> {
+        if right.is_zero() {
+            // any left operand works, so this can only be solved forwards
+            return if result.is_zero() {
+                Inverse::Unsupported
+            } else {
+                Inverse::Impossible
+            };
+        }
+        match checked_rem(result, right) {
+            Some(remainder) if remainder.is_zero() => result.checked_div(right).into(),
+            _ => Inverse::Impossible,
+        }
+    }
+
+    fn keeps_non_negative(&self) -> bool {
+        true
+    }
+}
+
+// 10 to the power of the number of digits in `value`, so that concatenating `a` and `value`
+// is `a * concat_multiplier(value) + value`
+fn concat_multiplier<N: Number>(value: &N) -> Option<N> {
+    let ten = N::from_u8(10)?;
+    let mut multiplier = ten.clone();
+    while multiplier <= *value {
+        multiplier = multiplier.checked_mul(&ten)?;
+    }
+    Some(multiplier)
+}
+
+impl<N: Number> Operator<N> for Concatenate {
+    fn symbol(&self) -> &str {
+        "||"
+    }
+
+    fn apply(&self, left: &N, right: &N) -> Option<N> {
+        if *left < N::zero() || *right < N::zero() {
+            return None;
+        }
+        left.checked_mul(&concat_multiplier(right)?)?
+            .checked_add(right)
+    }
+
+    fn inverse(&self, result: &N, right: &N) -> Inverse<N> {
+        if *result < N::zero() || *right < N::zero() {
+            return Inverse::Impossible;
+        }
+        let Some(multiplier) = concat_multiplier(right) else {
+            return Inverse::Impossible;
+        };
+        match checked_rem(result, &multiplier) {
+            Some(suffix) if suffix == *right => result.checked_div(&multiplier).into(),
+            _ => Inverse::Impossible,
+        }
+    }
+
+    fn keeps_non_negative(&self) -> bool {
+        true
+    }
+}
+
+impl<N: Number> Operator<N> for Subtract {
+    fn symbol(&self) -> &str {
+        "-"
+    }
+
+    fn apply(&self, left: &N, right: &N) -> Option<N> {
+        left.checked_sub(right)
+    }
+
+    fn inverse(&self, result: &N, right: &N) -> Inverse<N> {
+        result.checked_add(right).into()
+    }
+}
+
+// exact division only, so that every result has a single left operand
+impl<N: Number> Operator<N> for Divide {
+    fn symbol(&self) -> &str {
+        "/"
+    }
+
+    fn apply(&self, left: &N, right: &N) -> Option<N> {
+        if !checked_rem(left, right)?.is_zero() {
+            return None;
+        }
+        left.checked_div(right)
+    }
+
+    fn inverse(&self, result: &N, right: &N) -> Inverse<N> {
+        if right.is_zero() {
+            return Inverse::Impossible;
+        }
+        result.checked_mul(right).into()
+    }
+
+    fn keeps_non_negative(&self) -> bool {
+        true
+    }
+}
+
+impl<N: Number> Operator<N> for Power {
+    fn symbol(&self) -> &str {
+        "^"
+    }
+
+    fn apply(&self, left: &N, right: &N) -> Option<N> {
+        num_traits::checked_pow(left.clone(), right.to_usize()?)
+    }
+
+    fn keeps_non_negative(&self) -> bool {
+        true
+    }
+}
+
+impl<N: Number> Operator<N> for Max {
+    fn symbol(&self) -> &str {
+        "max"
+    }
+
+    fn apply(&self, left: &N, right: &N) -> Option<N> {
+        Some(left.max(right).clone())
+    }
+
+    fn keeps_non_negative(&self) -> bool {
+        true
+    }
+}
+
+// wraps another operator so that a missing result panics rather than being pruned
+struct Strict<N>(Box<dyn Operator<N>>);
+
+impl<N> Operator<N> for Strict<N> {
+    fn symbol(&self) -> &str {
+        self.0.symbol()
+    }
+
+    fn apply(&self, left: &N, right: &N) -> Option<N> {
+        self.0.apply(left, right)
+    }
+
+    fn inverse(&self, result: &N, right: &N) -> Inverse<N> {
+        self.0.inverse(result, right)
+    }
+
+    fn overflow(&self) -> Overflow {
+        Overflow::Panic
+    }
+
+    fn keeps_non_negative(&self) -> bool {
+        self.0.keeps_non_negative()
+    }
+}
+
+// a trailing `!` makes the operator strict, e.g. `*!`
+fn operator<N: Number>(symbol: &str) -> Box<dyn Operator<N>> {
+    if let Some(symbol) = symbol.strip_suffix('!') {
+        return Box::new(Strict(operator(symbol)));
+    }
+    match symbol {
+        "+" => Box::new(Add),
+        "*" => Box::new(Multiply),
+        "||" => Box::new(Concatenate),
+        "-" => Box::new(Subtract),
+        "/" => Box::new(Divide),
+        "^" => Box::new(Power),
+        "max" => Box::new(Max),
+        _ => panic!("Invalid operator: {}", symbol),
+    }
+}
+
+fn operators<N: Number>(symbols: &str) -> Vec<Box<dyn Operator<N>>> {
+    symbols.split(',').map(str::trim).map(operator).collect()
+}
+
+fn evaluate<N: Number>(operator: &dyn Operator<N>, left: &N, right: &N) -> Option<N> {
+    let result = operator.apply(left, right);
+    if result.is_none() && matches!(operator.overflow(), Overflow::Panic) {
+        panic!("No result for {} {} {}", left, operator.symbol(), right);
+    }
+    result
+}
+
+// the assignments of operators (by index) between the values that reach a target, with
+// one witness assignment kept so the solution can be shown
+#[derive(Clone)]
+struct Solutions {
+    witness: Option<Vec<usize>>,
+    count: u64,
+}
+
+impl Solutions {
+    fn none() -> Self {
+        Solutions {
+            witness: None,
+            count: 0,
         }
+    }
 
-        if result == line.target {
-            return true;
+    fn add(&mut self, other: &Solutions, operator_index: usize) {
+        if self.witness.is_none() {
+            self.witness = other.witness.clone().map(|mut witness| {
+                witness.push(operator_index);
+                witness
+            });
         }
+        self.count += other.count;
     }
-    false
+}
+
+// every value the operators can produce from the values, evaluated left to right
+fn forward_values<N: Number>(
+    values: &[N],
+    operators: &[Box<dyn Operator<N>>],
+) -> HashMap<N, Solutions> {
+    let (first, rest) = values.split_first().unwrap();
+    let start = Solutions {
+        witness: Some(Vec::new()),
+        count: 1,
+    };
+    rest.iter()
+        .fold(HashMap::from([(first.clone(), start)]), |reached, value| {
+            let mut next: HashMap<N, Solutions> = HashMap::new();
+            for (left, solutions) in reached.iter() {
+                for (index, op) in operators.iter().enumerate() {
+                    if let Some(result) = evaluate(op.as_ref(), left, value) {
+                        next.entry(result)
+                            .or_insert_with(Solutions::none)
+                            .add(solutions, index);
+                    }
+                }
+            }
+            next
+        })
+}
+
+// with no negative values and operators that keep non-negative operands non-negative,
+// every partial result is non-negative, so a negative target can never be reached
+fn negative_targets_unreachable<N: Number>(
+    values: &[N],
+    operators: &[Box<dyn Operator<N>>],
+) -> bool {
+    values.iter().all(|value| *value >= N::zero())
+        && operators.iter().all(|op| op.keeps_non_negative())
+}
+
+// works right to left, undoing the last operator to get the target for the remaining values;
+// operators without an inverse fall back to evaluating the remaining values forwards.
+// Stops at the first solution unless `count_all` is set.
+fn solve<N: Number>(
+    target: &N,
+    values: &[N],
+    operators: &[Box<dyn Operator<N>>],
+    count_all: bool,
+) -> Solutions {
+    let prune_negative = negative_targets_unreachable(values, operators);
+    search(target, values, operators, count_all, prune_negative)
+}
+
+fn search<N: Number>(
+    target: &N,
+    values: &[N],
+    operators: &[Box<dyn Operator<N>>],
+    count_all: bool,
+    prune_negative: bool,
+) -> Solutions {
+    if prune_negative && *target < N::zero() {
+        return Solutions::none();
+    }
+    let (last, rest) = values.split_last().unwrap();
+    if rest.is_empty() {
+        return if target == last {
+            Solutions {
+                witness: Some(Vec::new()),
+                count: 1,
+            }
+        } else {
+            Solutions::none()
+        };
+    }
+
+    let mut solutions = Solutions::none();
+    for (index, op) in operators.iter().enumerate() {
+        match op.inverse(target, last) {
+            Inverse::Operand(left) => {
+                solutions.add(
+                    &search(&left, rest, operators, count_all, prune_negative),
+                    index,
+                );
+            }
+            Inverse::Impossible => {}
+            Inverse::Unsupported => {
+                for (left, reached) in forward_values(rest, operators) {
+                    if evaluate(op.as_ref(), &left, last).as_ref() == Some(target) {
+                        solutions.add(&reached, index);
+                    }
+                }
+            }
+        }
+        if solutions.count > 0 && !count_all {
+            break;
+        }
+    }
+    solutions
+}
+
+fn can_reach<N: Number>(target: &N, values: &[N], operators: &[Box<dyn Operator<N>>]) -> bool {
+    solve(target, values, operators, false).count > 0
+}
+
+fn process_line<N: Number>(line: &Line<N>, operators: &[Box<dyn Operator<N>>]) -> bool {
+    can_reach(&line.target, &line.test_values, operators)
+}
+
+fn format_witness<N: Number>(
+    line: &Line<N>,
+    witness: &[usize],
+    operators: &[Box<dyn Operator<N>>],
+) -> String {
+    let expression = witness.iter().zip(line.test_values[1..].iter()).fold(
+        line.test_values[0].to_string(),
+        |expression, (&index, value)| {
+            format!("{} {} {}", expression, operators[index].symbol(), value)
+        },
+    );
+    format!("{} = {}", line.target, expression)
+}
+
+fn total_calibration<N: Number>(input: &str, operators: &[Box<dyn Operator<N>>]) -> N {
+    input
+        .lines()
+        .map(parse_line)
+        .filter(|line| process_line(line, operators))
+        .fold(N::zero(), |total, line| {
+            total
+                .checked_add(&line.target)
+                .expect("Calibration total overflowed")
+        })
 }
 
 pub fn part_a(input: &str) -> i64 {
-    let operators = vec!['+', '*'];
-    let lines = input.lines().map(parse_line);
-    lines
-        .into_iter()
-        .filter(|line| process_line(line, &operators))
-        .map(|line| line.target)
-        .sum()
+    total_calibration(input, &operators("+,*"))
 }
 
 pub fn part_b(input: &str) -> i64 {
-    let operators = vec!['+', '*', '|'];
-    let lines = input.lines().map(parse_line);
-    lines
-        .into_iter()
-        .filter(|line| process_line(line, &operators))
-        .map(|line| line.target)
-        .sum()
+    total_calibration(input, &operators("+,*,||"))
+}
+
+// solves with any comma-separated set of operators, e.g. `+,*!,-,max`, using checked
+// `i64`/`u64` (overflowing branches are pruned), `i128` or arbitrary precision (`big`)
+pub fn calibrate(input: &str, symbols: &str, backend: &str) -> String {
+    match backend {
+        "i64" => total_calibration::<i64>(input, &operators(symbols)).to_string(),
+        "u64" => total_calibration::<u64>(input, &operators(symbols)).to_string(),
+        "i128" => total_calibration::<i128>(input, &operators(symbols)).to_string(),
+        "big" => total_calibration::<BigInt>(input, &operators(symbols)).to_string(),
+        _ => panic!("Invalid numeric backend: {}", backend),
+    }
+}
+
+// one witness expression per satisfiable line, optionally with the number of valid
+// operator assignments
+pub fn explain(input: &str, part: char, count_all: bool) -> String {
+    let operators = match part {
+        'a' => operators::<BigInt>("+,*"),
+        'b' => operators("+,*,||"),
+        _ => panic!("Invalid part: {}", part),
+    };
+    input
+        .lines()
+        .map(parse_line)
+        .filter_map(|line| {
+            let solutions = solve(&line.target, &line.test_values, &operators, count_all);
+            let expression = format_witness(&line, solutions.witness.as_ref()?, &operators);
+            if count_all {
+                let noun = if solutions.count == 1 {
+                    "assignment"
+                } else {
+                    "assignments"
+                };
+                Some(format!("{} ({} {})", expression, solutions.count, noun))
+            } else {
+                Some(expression)
+            }
+        })
+        .collect::<Vec<String>>()
+        .join("\n")
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use advent_of_code_2024::read_test_file;
+    use advent_of_code_2024::{read_test_file, trimmed_lines};
+
+    fn reaches(target: i64, values: &[i64], symbols: &str) -> bool {
+        can_reach(&target, values, &operators(symbols))
+    }
+
+    fn count_solutions(target: i64, values: &[i64], symbols: &str) -> u64 {
+        solve(&target, values, &operators(symbols), true).count
+    }
 
     #[test]
     fn test_parse_line() {
         let input = "5: 1 2 3 4 5";
-        let actual = parse_line(input);
+        let actual = parse_line::<i64>(input);
         let expected = Line {
             target: 5,
             test_values: vec![1, 2, 3, 4, 5],
@@ -78,6 +564,156 @@ mod tests {
         assert_eq!(actual.test_values, expected.test_values);
     }
 
+    #[test]
+    fn test_concat_multiplier() {
+        assert_eq!(concat_multiplier(&0i64), Some(10));
+        assert_eq!(concat_multiplier(&9i64), Some(10));
+        assert_eq!(concat_multiplier(&10i64), Some(100));
+        assert_eq!(concat_multiplier(&345i64), Some(1000));
+        assert_eq!(concat_multiplier(&i64::MAX), None);
+    }
+
+    #[test]
+    fn test_concatenate() {
+        assert_eq!(Concatenate.apply(&12i64, &345), Some(12345));
+        assert_eq!(Concatenate.apply(&(i64::MAX / 10), &345), None);
+        assert!(matches!(
+            Concatenate.inverse(&12345i64, &345),
+            Inverse::Operand(12)
+        ));
+        assert!(matches!(
+            Concatenate.inverse(&12345i64, &45),
+            Inverse::Operand(123)
+        ));
+        assert!(matches!(
+            Concatenate.inverse(&12345i64, &44),
+            Inverse::Impossible
+        ));
+    }
+
+    #[test]
+    fn test_can_reach() {
+        assert!(reaches(3267, &[81, 40, 27], "+,*"));
+        assert!(!reaches(156, &[15, 6], "+,*"));
+        assert!(reaches(156, &[15, 6], "+,*,||"));
+        assert!(reaches(7290, &[6, 8, 6, 15], "+,*,||"));
+        assert!(!reaches(21037, &[9, 7, 18, 13], "+,*,||"));
+    }
+
+    #[test]
+    fn test_can_reach_concatenation_needs_matching_suffix() {
+        assert!(reaches(1210, &[12, 10], "||"));
+        assert!(!reaches(1201, &[12, 10], "||"));
+        assert!(!reaches(10, &[1, 10], "||"));
+    }
+
+    #[test]
+    fn test_can_reach_user_defined_operators() {
+        assert!(reaches(-5, &[3, 8], "+,-"));
+        assert!(reaches(4, &[24, 3, 2], "/"));
+        assert!(!reaches(5, &[24, 5], "/"));
+        assert!(reaches(81, &[3, 2, 2], "^"));
+        assert!(reaches(17, &[2, 9, 17], "max,+"));
+        assert!(reaches(12, &[2, 9, 3], "max,+"));
+        assert!(reaches(0, &[7, 0], "*"));
+    }
+
+    #[test]
+    fn test_negative_targets_unreachable() {
+        assert!(negative_targets_unreachable(
+            &[81, 40],
+            &operators("+,*,||")
+        ));
+        assert!(negative_targets_unreachable(
+            &[81, 40],
+            &operators("max,^,*!")
+        ));
+        assert!(!negative_targets_unreachable(&[81, 40], &operators("+,-")));
+        assert!(!negative_targets_unreachable(&[81, -40], &operators("+,*")));
+        assert!(!reaches(-5, &[3, 8], "+,*"));
+        assert!(reaches(-5, &[3, -8], "+,*"));
+    }
+
+    #[test]
+    #[should_panic(expected = "No result for")]
+    fn test_strict_operator_panics_on_overflow() {
+        reaches(1, &[i64::MAX, 2, 3], "max,*!");
+    }
+
+    #[test]
+    fn test_operator_prunes_on_overflow() {
+        assert!(!reaches(1, &[i64::MAX, 2, 3], "max,*"));
+    }
+
+    #[test]
+    fn test_wider_backends_recover_from_intermediate_overflow() {
+        // 2^62 * 2 overflows i64 before subtracting 10 brings it back in range
+        let input = "9223372036854775798: 4611686018427387904 2 10";
+        assert_eq!(calibrate(input, "*,-", "i64"), "0");
+        assert_eq!(calibrate(input, "*,-", "i128"), "9223372036854775798");
+        assert_eq!(calibrate(input, "*,-", "big"), "9223372036854775798");
+    }
+
+    #[test]
+    fn test_big_backend_long_equation() {
+        let values = vec!["2"; 100].join(" ");
+        let input = format!("1267650600228229401496703205376: {}", values);
+        assert_eq!(
+            calibrate(&input, "*", "big"),
+            "1267650600228229401496703205376"
+        );
+        let input = format!("1267650600228229401496703205377: {}", values);
+        assert_eq!(calibrate(&input, "*", "big"), "0");
+    }
+
+    #[test]
+    fn test_unsigned_backend() {
+        let input = read_test_file(7);
+        assert_eq!(calibrate(&input, "+,*,||", "u64"), "11387");
+    }
+
+    #[test]
+    fn test_solve_witness() {
+        let line = parse_line::<i64>("3267: 81 40 27");
+        let operators = operators("+,*");
+        let solutions = solve(&line.target, &line.test_values, &operators, false);
+        let witness = solutions.witness.unwrap();
+        assert_eq!(
+            format_witness(&line, &witness, &operators),
+            "3267 = 81 * 40 + 27"
+        );
+    }
+
+    #[test]
+    fn test_solve_counts_all_assignments() {
+        assert_eq!(count_solutions(3267, &[81, 40, 27], "+,*"), 2);
+        assert_eq!(count_solutions(4, &[2, 2], "+,*"), 2);
+        assert_eq!(count_solutions(5, &[2, 2], "+,*"), 0);
+        assert_eq!(count_solutions(4, &[2, 2, 2], "max,+"), 2);
+    }
+
+    #[test]
+    fn test_explain() {
+        let input = read_test_file(7);
+        let expected = r#"
+            190 = 10 * 19 (1 assignment)
+            3267 = 81 * 40 + 27 (2 assignments)
+            156 = 15 || 6 (1 assignment)
+            7290 = 6 * 8 || 6 * 15 (1 assignment)
+            192 = 17 || 8 + 14 (1 assignment)
+            292 = 11 + 6 * 16 + 20 (1 assignment)
+        "#;
+        let actual = explain(&input, 'b', true);
+        let expected_lines = trimmed_lines(expected);
+        assert_eq!(actual.lines().collect::<Vec<&str>>(), expected_lines);
+    }
+
+    #[test]
+    fn test_calibrate() {
+        let input = read_test_file(7);
+        assert_eq!(calibrate(&input, "+,*", "i64"), "3749");
+    }
+
     #[test]
     fn test_part_a() {
         let input = read_test_file(7);
@@ -0,0 +1,116 @@
+use advent_of_code_2024::gcd;
+use advent_of_code_2024::grid::{build_grid, in_bounds};
+use itertools::Itertools;
+use std::collections::{HashMap, HashSet};
+
+type Position = (i64, i64);
+
+// antenna positions keyed by frequency; anything other than `.` is an antenna
+fn antennas(grid: &[Vec<char>]) -> HashMap<char, Vec<Position>> {
+    grid.iter()
+        .enumerate()
+        .flat_map(|(y, row)| {
+            row.iter()
+                .enumerate()
+                .filter(|(_, &cell)| cell != '.')
+                .map(move |(x, &cell)| (cell, (x as i64, y as i64)))
+        })
+        .into_group_map()
+}
+
+fn antenna_pairs(grid: &[Vec<char>]) -> Vec<(Position, Position)> {
+    antennas(grid)
+        .into_values()
+        .flat_map(|positions| positions.into_iter().tuple_combinations())
+        .collect()
+}
+
+// the points twice as far from one antenna of the pair as from the other
+fn antinodes(grid: &[Vec<char>], a: Position, b: Position) -> Vec<Position> {
+    [
+        (2 * a.0 - b.0, 2 * a.1 - b.1),
+        (2 * b.0 - a.0, 2 * b.1 - a.1),
+    ]
+    .into_iter()
+    .filter(|&p| in_bounds(grid, p))
+    .collect()
+}
+
+// every grid point on the line through the pair. the step is reduced by the gcd of the
+// offset so points between lattice-aligned antennas are not skipped
+fn harmonics(grid: &[Vec<char>], a: Position, b: Position) -> Vec<Position> {
+    let (dx, dy) = (b.0 - a.0, b.1 - a.1);
+    let divisor = gcd(dx, dy);
+    let step = (dx / divisor, dy / divisor);
+
+    let mut points = Vec::new();
+    for (sign, start) in [(1, a), (-1, (a.0 - step.0, a.1 - step.1))] {
+        let mut p = start;
+        while in_bounds(grid, p) {
+            points.push(p);
+            p = (p.0 + sign * step.0, p.1 + sign * step.1);
+        }
+    }
+    points
+}
+
+fn count_antinodes(
+    input: &str,
+    find: fn(&[Vec<char>], Position, Position) -> Vec<Position>,
+) -> u32 {
+    let grid = build_grid(input);
+    antenna_pairs(&grid)
+        .into_iter()
+        .flat_map(|(a, b)| find(&grid, a, b))
+        .collect::<HashSet<Position>>()
+        .len() as u32
+}
+
+pub fn part_a(input: &str) -> u32 {
+    count_antinodes(input, antinodes)
+}
+
+pub fn part_b(input: &str) -> u32 {
+    count_antinodes(input, harmonics)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use advent_of_code_2024::read_test_file;
+
+    #[test]
+    fn test_antennas() {
+        let grid = build_grid(&read_test_file(8));
+        let antennas = antennas(&grid);
+        assert_eq!(antennas[&'0'], vec![(8, 1), (5, 2), (7, 3), (4, 4)]);
+        assert_eq!(antennas[&'A'], vec![(6, 5), (8, 8), (9, 9)]);
+    }
+
+    #[test]
+    fn test_harmonics() {
+        let grid = build_grid("..........\n".repeat(10).trim());
+        assert_eq!(
+            harmonics(&grid, (2, 2), (6, 4)),
+            vec![(2, 2), (4, 3), (6, 4), (8, 5), (0, 1)]
+        );
+    }
+
+    #[test]
+    fn test_part_a() {
+        let input = read_test_file(8);
+        assert_eq!(part_a(&input), 14);
+    }
+
+    #[test]
+    fn test_part_b() {
+        let input = read_test_file(8);
+        assert_eq!(part_b(&input), 34);
+    }
+
+    #[test]
+    fn test_part_b_single_frequency() {
+        let input = "T.........\n...T......\n.T........\n..........\n..........\n..........\n..........\n..........\n..........\n..........";
+        assert_eq!(part_b(input), 9);
+    }
+}
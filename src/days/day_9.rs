@@ -0,0 +1,197 @@
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
+
+#[derive(PartialEq, Clone, Copy, Debug)]
+struct Span {
+    start: usize,
+    len: usize,
+}
+
+// files are numbered by their position in the map, gaps lie between consecutive files
+struct DiskMap {
+    files: Vec<Span>,
+    gaps: Vec<Span>,
+}
+
+fn parse_disk_map(input: &str) -> DiskMap {
+    let mut disk_map = DiskMap {
+        files: Vec::new(),
+        gaps: Vec::new(),
+    };
+    let mut start = 0;
+    for (i, c) in input.trim().chars().enumerate() {
+        let len = c
+            .to_digit(10)
+            .unwrap_or_else(|| panic!("Invalid disk map digit: {}", c)) as usize;
+        let span = Span { start, len };
+        if i % 2 == 0 {
+            disk_map.files.push(span);
+        } else {
+            disk_map.gaps.push(span);
+        }
+        start += len;
+    }
+    disk_map
+}
+
+// the sum of position * id over every block of the file
+fn span_checksum(id: usize, span: &Span) -> u64 {
+    let (start, len) = (span.start as u64, span.len as u64);
+    id as u64 * (start * len + len * len.saturating_sub(1) / 2)
+}
+
+// the file id stored in each block, or None for free space
+fn blocks(disk_map: &DiskMap) -> Vec<Option<usize>> {
+    let mut blocks = Vec::new();
+    for (id, file) in disk_map.files.iter().enumerate() {
+        blocks.resize(file.start, None);
+        blocks.extend(std::iter::repeat_n(Some(id), file.len));
+    }
+    blocks
+}
+
+// moves the last file block into the first free block until there are no gaps
+fn compact_blocks(disk_map: &DiskMap) -> Vec<Option<usize>> {
+    let mut blocks = blocks(disk_map);
+    let mut free = 0;
+    let mut last = blocks.len();
+    loop {
+        while free < blocks.len() && blocks[free].is_some() {
+            free += 1;
+        }
+        while last > 0 && blocks[last - 1].is_none() {
+            last -= 1;
+        }
+        if last == 0 || free >= last - 1 {
+            return blocks;
+        }
+        blocks.swap(free, last - 1);
+    }
+}
+
+// the gaps on either side of an empty file are one free span, so are joined into one gap
+fn merged_gaps(disk_map: &DiskMap) -> Vec<Span> {
+    let mut gaps: Vec<Span> = Vec::new();
+    for (i, gap) in disk_map.gaps.iter().enumerate() {
+        match gaps.last_mut() {
+            Some(last) if disk_map.files[i].len == 0 => last.len += gap.len,
+            _ => gaps.push(*gap),
+        }
+    }
+    gaps
+}
+
+// moves each file once, highest id first, into the leftmost gap that fits it.
+//
+// gaps are indexed by size in min-heaps of their start, so the leftmost gap that fits is
+// the smallest top of the heaps for sizes at least the file's length. what remains of a
+// gap after a move goes back into the heap for its new size; gaps freed by moved files
+// lie right of every file still to move so are never needed
+fn compact_files(disk_map: &DiskMap) -> Vec<Span> {
+    let gaps = merged_gaps(disk_map);
+    let max_size = gaps.iter().map(|g| g.len).max().unwrap_or(0);
+    let mut gaps_by_size: Vec<BinaryHeap<Reverse<usize>>> = vec![BinaryHeap::new(); max_size + 1];
+    for gap in gaps.iter().filter(|g| g.len > 0) {
+        gaps_by_size[gap.len].push(Reverse(gap.start));
+    }
+
+    let mut files = disk_map.files.clone();
+    for file in files.iter_mut().rev().filter(|f| f.len > 0) {
+        let leftmost = (file.len..gaps_by_size.len())
+            .filter_map(|size| {
+                gaps_by_size[size]
+                    .peek()
+                    .map(|&Reverse(start)| (start, size))
+            })
+            .min();
+        let Some((start, size)) = leftmost else {
+            continue;
+        };
+        if start >= file.start {
+            continue;
+        }
+        gaps_by_size[size].pop();
+        if size > file.len {
+            gaps_by_size[size - file.len].push(Reverse(start + file.len));
+        }
+        file.start = start;
+    }
+    files
+}
+
+pub fn part_a(input: &str) -> u64 {
+    let disk_map = parse_disk_map(input);
+    compact_blocks(&disk_map)
+        .iter()
+        .enumerate()
+        .filter_map(|(position, id)| id.map(|id| (position * id) as u64))
+        .sum()
+}
+
+pub fn part_b(input: &str) -> u64 {
+    let disk_map = parse_disk_map(input);
+    compact_files(&disk_map)
+        .iter()
+        .enumerate()
+        .map(|(id, file)| span_checksum(id, file))
+        .sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use advent_of_code_2024::read_test_file;
+
+    fn render(blocks: &[Option<usize>]) -> String {
+        blocks
+            .iter()
+            .map(|b| b.map_or('.', |id| char::from_digit(id as u32, 10).unwrap()))
+            .collect()
+    }
+
+    #[test]
+    fn test_compact_blocks() {
+        let disk_map = parse_disk_map("12345");
+        assert_eq!(render(&blocks(&disk_map)), "0..111....22222");
+        assert_eq!(render(&compact_blocks(&disk_map)), "022111222......");
+    }
+
+    #[test]
+    fn test_compact_files() {
+        let disk_map = parse_disk_map(&read_test_file(9));
+        let files = compact_files(&disk_map);
+        let mut blocks = vec![None; 42];
+        for (id, file) in files.iter().enumerate() {
+            blocks[file.start..file.start + file.len].fill(Some(id));
+        }
+        assert_eq!(
+            render(&blocks),
+            "00992111777.44.333....5555.6666.....8888.."
+        );
+    }
+
+    #[test]
+    fn test_span_checksum() {
+        let span = Span { start: 4, len: 3 };
+        assert_eq!(span_checksum(2, &span), 2 * (4 + 5 + 6));
+        assert_eq!(span_checksum(2, &Span { start: 4, len: 0 }), 0);
+    }
+
+    #[test]
+    fn test_part_a() {
+        let input = read_test_file(9);
+        assert_eq!(part_a(&input), 1928);
+    }
+
+    #[test]
+    fn test_part_b() {
+        let input = read_test_file(9);
+        assert_eq!(part_b(&input), 2858);
+    }
+
+    #[test]
+    fn test_part_b_empty_file() {
+        // file 2 fits in the gaps either side of the empty file 1
+        assert_eq!(part_b("23034"), 28);
+    }
+}
@@ -0,0 +1,121 @@
+pub struct Direction {
+    pub x: i64,
+    pub y: i64,
+    pub symbol: char,
+}
+
+// headings in clockwise order, so turning right moves to the next entry
+pub static DIRECTIONS: [Direction; 4] = [
+    Direction {
+        x: 0,
+        y: -1,
+        symbol: '^',
+    },
+    Direction {
+        x: 1,
+        y: 0,
+        symbol: '>',
+    },
+    Direction {
+        x: 0,
+        y: 1,
+        symbol: 'v',
+    },
+    Direction {
+        x: -1,
+        y: 0,
+        symbol: '<',
+    },
+];
+
+pub enum TurnPolicy {
+    Right,
+    Left,
+    Reverse,
+}
+
+impl From<&str> for TurnPolicy {
+    fn from(s: &str) -> Self {
+        match s {
+            "right" => TurnPolicy::Right,
+            "left" => TurnPolicy::Left,
+            "reverse" => TurnPolicy::Reverse,
+            _ => panic!("Invalid turn policy: {}", s),
+        }
+    }
+}
+
+impl TurnPolicy {
+    pub fn turn(&self, direction: usize) -> usize {
+        let quarter_turns = match self {
+            TurnPolicy::Right => 1,
+            TurnPolicy::Reverse => 2,
+            TurnPolicy::Left => 3,
+        };
+        (direction + quarter_turns) % DIRECTIONS.len()
+    }
+}
+
+// a position on the grid with a heading, an index into DIRECTIONS
+#[derive(PartialEq, Clone, Copy, Debug)]
+pub struct State {
+    pub position: (i64, i64),
+    pub direction: usize,
+}
+
+pub fn build_grid(input: &str) -> Vec<Vec<char>> {
+    input.lines().map(|line| line.chars().collect()).collect()
+}
+
+pub fn in_bounds(grid: &[Vec<char>], position: (i64, i64)) -> bool {
+    position.0 >= 0
+        && position.0 < grid[0].len() as i64
+        && position.1 >= 0
+        && position.1 < grid.len() as i64
+}
+
+// a distinct index for every position and heading on the grid
+pub fn state_index(grid: &[Vec<char>], state: &State) -> usize {
+    let cell = state.position.1 as usize * grid[0].len() + state.position.0 as usize;
+    cell * DIRECTIONS.len() + state.direction
+}
+
+static NEIGHBOURS: [(i64, i64); 4] = [(0, -1), (1, 0), (0, 1), (-1, 0)];
+
+// the cells horizontally and vertically next to (x, y) that lie within the grid
+pub fn neighbours<T>(grid: &[Vec<T>], (x, y): (usize, usize)) -> Vec<(usize, usize)> {
+    NEIGHBOURS
+        .iter()
+        .map(|(dx, dy)| (x as i64 + dx, y as i64 + dy))
+        .filter(|&(nx, ny)| ny >= 0 && (ny as usize) < grid.len() && nx >= 0)
+        .filter(|&(nx, ny)| (nx as usize) < grid[ny as usize].len())
+        .map(|(nx, ny)| (nx as usize, ny as usize))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_turn_policy() {
+        assert_eq!(TurnPolicy::Right.turn(3), 0);
+        assert_eq!(TurnPolicy::Left.turn(0), 3);
+        assert_eq!(TurnPolicy::from("reverse").turn(1), 3);
+    }
+
+    #[test]
+    fn test_in_bounds() {
+        let grid = build_grid("...\n...");
+        assert!(in_bounds(&grid, (2, 1)));
+        assert!(!in_bounds(&grid, (3, 1)));
+        assert!(!in_bounds(&grid, (0, -1)));
+    }
+
+    #[test]
+    fn test_neighbours() {
+        let grid = build_grid("...\n...");
+        assert_eq!(neighbours(&grid, (0, 0)), vec![(1, 0), (0, 1)]);
+        assert_eq!(neighbours(&grid, (1, 1)), vec![(1, 0), (2, 1), (0, 1)]);
+    }
+}
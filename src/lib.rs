@@ -1,5 +1,9 @@
 use std::{env, fs};
 
+pub mod comparison;
+pub mod grid;
+pub mod regions;
+
 pub fn to_u32(i: &str) -> u32 {
     i.trim().parse::<u32>().unwrap()
 }
@@ -12,6 +16,21 @@ pub fn to_i64(i: &str) -> i64 {
     i.trim().parse::<i64>().unwrap()
 }
 
+pub fn gcd(a: i64, b: i64) -> i64 {
+    if b == 0 {
+        a.abs()
+    } else {
+        gcd(b, a % b)
+    }
+}
+
+pub fn lcm(a: i64, b: i64) -> i64 {
+    if a == 0 || b == 0 {
+        return 0;
+    }
+    (a / gcd(a, b) * b).abs()
+}
+
 pub fn read_file(day: u32) -> String {
     read_file_path(day, vec!["inputs"])
 }
@@ -20,10 +39,16 @@ pub fn read_test_file(day: u32) -> String {
     read_file_path(day, vec!["inputs", "test"])
 }
 
+// the lines of `s` with surrounding whitespace removed, for comparing against indented
+// expected output in tests
+pub fn trimmed_lines(s: &str) -> Vec<&str> {
+    s.trim().lines().map(str::trim).collect()
+}
+
 fn read_file_path(day: u32, path: Vec<&str>) -> String {
     let cwd = env::current_dir().unwrap();
     let filename = format!("day_{}.txt", day);
     let filepath = path.iter().fold(cwd, |c, x| c.join(x));
     let f = fs::read_to_string(filepath.join(filename.clone()));
-    f.expect(&format!("could not open input file {}", filename))
+    f.unwrap_or_else(|_| panic!("could not open input file {}", filename))
 }
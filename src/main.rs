@@ -1,14 +1,15 @@
 extern crate argparse;
 
 use advent_of_code_2024::read_file;
-use argparse::{ArgumentParser, Store};
+use argparse::{ArgumentParser, Store, StoreTrue};
 use days::*;
 mod days;
-use std::fmt;
+use std::{fmt, fs};
 
 #[derive(Debug)]
 enum ReturnType {
     UInt32(u32),
+    UInt64(u64),
     Int64(i64),
     String(String),
 }
@@ -19,6 +20,12 @@ impl From<u32> for ReturnType {
     }
 }
 
+impl From<u64> for ReturnType {
+    fn from(u: u64) -> Self {
+        Self::UInt64(u)
+    }
+}
+
 impl From<i64> for ReturnType {
     fn from(i: i64) -> Self {
         Self::Int64(i)
@@ -35,15 +42,181 @@ impl fmt::Display for ReturnType {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         match self {
             ReturnType::UInt32(value) => write!(f, "{}", value),
+            ReturnType::UInt64(value) => write!(f, "{}", value),
             ReturnType::Int64(value) => write!(f, "{}", value),
             ReturnType::String(value) => write!(f, "{}", value),
         }
     }
 }
 
+fn solve(day: u32, part: char, input: &str) {
+    let result: ReturnType = match (day, part) {
+        (1, 'a') => day_1::part_a(input).into(),
+        (1, 'b') => day_1::part_b(input).into(),
+        (2, 'a') => day_2::part_a(input).into(),
+        (2, 'b') => day_2::part_b(input).into(),
+        (3, 'a') => day_3::part_a(input).into(),
+        (3, 'b') => day_3::part_b(input).into(),
+        (4, 'a') => day_4::part_a(input).into(),
+        (4, 'b') => day_4::part_b(input).into(),
+        (5, 'a') => day_5::part_a(input).into(),
+        (5, 'b') => day_5::part_b(input).into(),
+        (6, 'a') => day_6::part_a(input).into(),
+        (6, 'b') => day_6::part_b(input).into(),
+        (7, 'a') => day_7::part_a(input).into(),
+        (7, 'b') => day_7::part_b(input).into(),
+        (8, 'a') => day_8::part_a(input).into(),
+        (8, 'b') => day_8::part_b(input).into(),
+        (9, 'a') => day_9::part_a(input).into(),
+        (9, 'b') => day_9::part_b(input).into(),
+        (10, 'a') => day_10::part_a(input).into(),
+        (10, 'b') => day_10::part_b(input).into(),
+        (11, 'a') => day_11::part_a(input).into(),
+        (11, 'b') => day_11::part_b(input).into(),
+        (12, 'a') => day_12::part_a(input).into(),
+        (12, 'b') => day_12::part_b(input).into(),
+        (13, 'a') => day_13::part_a(input).into(),
+        (13, 'b') => day_13::part_b(input).into(),
+        (14, 'a') => day_14::part_a(input).into(),
+        (14, 'b') => day_14::part_b(input).into(),
+        (15, 'a') => day_15::part_a(input).into(),
+        (15, 'b') => day_15::part_b(input).into(),
+        (16, 'a') => day_16::part_a(input).into(),
+        (16, 'b') => day_16::part_b(input).into(),
+        (17, 'a') => day_17::part_a(input).into(),
+        (17, 'b') => day_17::part_b(input).into(),
+        (_, _) => panic!("Unrecognised day [{}] part [{}]", day, part),
+    };
+
+    println!("Result: {}", result)
+}
+
+fn render(day: u32, part: char, input: &str, style: &str, board: &str) {
+    let rendered = match day {
+        4 => day_4::render(input, part, style.into()),
+        14 => day_14::render(input, part, board.into()),
+        15 => day_15::render(input, part),
+        _ => panic!("Rendering is not supported for day [{}]", day),
+    };
+
+    println!("{}", rendered)
+}
+
+fn analyse(day: u32, input: &str) {
+    let analysis = match day {
+        5 => day_5::analyse(input),
+        _ => panic!("Analysis is not supported for day [{}]", day),
+    };
+
+    print!("{}", analysis)
+}
+
+fn graph(day: u32, input: &str, update: usize, format: &str) {
+    let format = if format.is_empty() { "dot" } else { format };
+    let graph = match day {
+        5 => day_5::graph(input, update, format.into()),
+        _ => panic!("Graph export is not supported for day [{}]", day),
+    };
+
+    println!("{}", graph)
+}
+
+fn simulate(day: u32, input: &str, turn: &str, boundary: &str, board: &str, step: i64) {
+    let simulation = match day {
+        6 => day_6::simulate_variant(input, turn.into(), boundary.into()),
+        14 => day_14::simulate(input, board.into(), step),
+        _ => panic!("Simulation is not supported for day [{}]", day),
+    };
+
+    println!("{}", simulation)
+}
+
+fn export(day: u32, part: char, input: &str, format: &str) {
+    let format = if format.is_empty() { "csv" } else { format };
+    let exported = match day {
+        6 => day_6::export(input, part, format),
+        _ => panic!("Export is not supported for day [{}]", day),
+    };
+
+    println!("{}", exported)
+}
+
+fn replay(day: u32, input: &str, path: &str, step: u32) {
+    let saved = fs::read_to_string(path).unwrap_or_else(|_| panic!("could not open {}", path));
+    let replayed = match day {
+        6 => day_6::replay(input, &saved, step),
+        _ => panic!("Replay is not supported for day [{}]", day),
+    };
+
+    println!("{}", replayed)
+}
+
+fn calibrate(day: u32, input: &str, operators: &str, backend: &str) {
+    let result: ReturnType = match day {
+        7 => day_7::calibrate(input, operators, backend).into(),
+        _ => panic!("Custom operators are not supported for day [{}]", day),
+    };
+
+    println!("Result: {}", result)
+}
+
+fn compare(day: u32, input: &str, pairing: &str) {
+    let comparison = match day {
+        1 => day_1::compare(input, pairing.into()),
+        _ => panic!("List comparison is not supported for day [{}]", day),
+    };
+
+    println!("{}", comparison)
+}
+
+fn blink(day: u32, input: &str, blinks: u32) {
+    let result: ReturnType = match day {
+        11 => day_11::stone_count(input, blinks).into(),
+        _ => panic!("Blinking is not supported for day [{}]", day),
+    };
+
+    println!("Result: {}", result)
+}
+
+fn check(day: u32, input: &str, policy: &str, explain_mode: bool) {
+    let policy = day_2::SafetyPolicy::from(policy);
+    match day {
+        2 if explain_mode => println!("{}", day_2::explain_check(input, &policy)),
+        2 => println!("Result: {}", day_2::check(input, &policy)),
+        _ => panic!("Safety policies are not supported for day [{}]", day),
+    }
+}
+
+fn explain(day: u32, part: char, input: &str, count: bool) {
+    let explanation = match day {
+        2 => day_2::explain(input, part),
+        7 => day_7::explain(input, part, count),
+        17 => day_17::disassemble(input),
+        _ => panic!("Explanations are not supported for day [{}]", day),
+    };
+
+    println!("{}", explanation)
+}
+
 fn main() {
     let mut day = 0;
     let mut part = ' ';
+    let mut command = "solve".to_string();
+    let mut style = "ansi".to_string();
+    let mut format = String::new();
+    let mut update = 0;
+    let mut turn = "right".to_string();
+    let mut boundary = "exit".to_string();
+    let mut path = String::new();
+    let mut step = 0;
+    let mut operators = "+,*".to_string();
+    let mut backend = "i64".to_string();
+    let mut policy = String::new();
+    let mut pairing = "strict".to_string();
+    let mut blinks = 25;
+    let mut board = "101x103".to_string();
+    let mut explain_mode = false;
+    let mut count = false;
     {
         // this block limits scope of borrows by ap.refer() method
         let mut ap = ArgumentParser::new();
@@ -52,37 +225,118 @@ fn main() {
             .add_option(&["-d", "--day"], Store, "Day to run");
         ap.refer(&mut part)
             .add_option(&["-p", "--part"], Store, "Part to run [a, b]");
+        ap.refer(&mut command).add_argument(
+            "command",
+            Store,
+            "Command to run [solve, render, analyse, graph, simulate, export, replay, calibrate, check, compare, blink]",
+        );
+        ap.refer(&mut style).add_option(
+            &["--style"],
+            Store,
+            "Highlight style for render [ansi, mask]",
+        );
+        ap.refer(&mut format).add_option(
+            &["--format"],
+            Store,
+            "Output format, [dot, mermaid] for graph and [csv, json] for export",
+        );
+        ap.refer(&mut update).add_option(
+            &["--update"],
+            Store,
+            "Update number to restrict graph to, 0 for all rules",
+        );
+        ap.refer(&mut turn).add_option(
+            &["--turn"],
+            Store,
+            "Guard turn policy for simulate [right, left, reverse]",
+        );
+        ap.refer(&mut boundary).add_option(
+            &["--boundary"],
+            Store,
+            "Guard boundary policy for simulate [exit, wrap, portal]",
+        );
+        ap.refer(&mut path)
+            .add_option(&["--path"], Store, "Saved export file to replay");
+        ap.refer(&mut step).add_option(
+            &["--step"],
+            Store,
+            "Step of the saved route to replay, or seconds to simulate day 14 robots for",
+        );
+        ap.refer(&mut operators).add_option(
+            &["--operators"],
+            Store,
+            "Comma-separated operators for calibrate [+, *, ||, -, /, ^, max], suffixed with ! to panic on overflow",
+        );
+        ap.refer(&mut backend).add_option(
+            &["--backend"],
+            Store,
+            "Numeric backend for calibrate [i64, u64, i128, big]",
+        );
+        ap.refer(&mut policy).add_option(
+            &["--policy"],
+            Store,
+            "Comma-separated safety policy for check, e.g. steps=1-3,direction=either,removals=1,flat",
+        );
+        ap.refer(&mut pairing).add_option(
+            &["--pairing"],
+            Store,
+            "Pairing policy for compare when the lists differ in length [strict, truncate, pad=N]",
+        );
+        ap.refer(&mut blinks)
+            .add_option(&["--blinks"], Store, "Number of blinks for blink");
+        ap.refer(&mut board).add_option(
+            &["--board"],
+            Store,
+            "Board size for day 14 render and simulate, as <width>x<height>",
+        );
+        ap.refer(&mut explain_mode).add_option(
+            &["--explain"],
+            StoreTrue,
+            "Show how each answer was reached when solving or checking",
+        );
+        ap.refer(&mut count).add_option(
+            &["--count"],
+            StoreTrue,
+            "Also count every valid solution with --explain",
+        );
         ap.parse_args_or_exit();
     }
 
-    if day < 1 || day > 25 {
+    if !(1..=25).contains(&day) {
         panic!("--day parameter must be specified and be between `1` and `25` inclusive");
     }
 
-    let valid_parts = vec!['a', 'b'];
-    if !valid_parts.contains(&part) {
+    let valid_parts = ['a', 'b'];
+    let part_required = ![
+        "analyse",
+        "graph",
+        "simulate",
+        "replay",
+        "calibrate",
+        "check",
+        "compare",
+        "blink",
+    ]
+    .contains(&command.as_str());
+    if part_required && !valid_parts.contains(&part) {
         panic!("--part parameter must be specified and be set either `a` or `b`");
     }
 
     let input = read_file(day);
 
-    let result: ReturnType = match (day, part) {
-        (1, 'a') => day_1::part_a(&input).into(),
-        (1, 'b') => day_1::part_b(&input).into(),
-        (2, 'a') => day_2::part_a(&input).into(),
-        (2, 'b') => day_2::part_b(&input).into(),
-        (3, 'a') => day_3::part_a(&input).into(),
-        (3, 'b') => day_3::part_b(&input).into(),
-        (4, 'a') => day_4::part_a(&input).into(),
-        (4, 'b') => day_4::part_b(&input).into(),
-        (5, 'a') => day_5::part_a(&input).into(),
-        (5, 'b') => day_5::part_b(&input).into(),
-        (6, 'a') => day_6::part_a(&input).into(),
-        (6, 'b') => day_6::part_b(&input).into(),
-        (7, 'a') => day_7::part_a(&input).into(),
-        (7, 'b') => day_7::part_b(&input).into(),
-        (_, _) => panic!("Unrecognised day [{}] part [{}]", day, part),
-    };
-
-    println!("Result: {}", result)
+    match command.as_str() {
+        "solve" if explain_mode => explain(day, part, &input, count),
+        "solve" => solve(day, part, &input),
+        "render" => render(day, part, &input, &style, &board),
+        "analyse" => analyse(day, &input),
+        "graph" => graph(day, &input, update, &format),
+        "simulate" => simulate(day, &input, &turn, &boundary, &board, step as i64),
+        "export" => export(day, part, &input, &format),
+        "replay" => replay(day, &input, &path, step),
+        "calibrate" => calibrate(day, &input, &operators, &backend),
+        "check" => check(day, &input, &policy, explain_mode),
+        "compare" => compare(day, &input, &pairing),
+        "blink" => blink(day, &input, blinks),
+        _ => panic!("Unrecognised command [{}]", command),
+    }
 }
@@ -0,0 +1,99 @@
+use crate::grid::neighbours;
+
+// connected components of a grid, where neighbouring cells are joined when `same_region`
+// holds for their values. cells are only joined horizontally and vertically
+pub struct Regions {
+    labels: Vec<Vec<usize>>,
+    cells: Vec<Vec<(usize, usize)>>,
+}
+
+impl Regions {
+    // flood fills from each unlabelled cell in reading order, so regions are numbered by
+    // their first cell
+    pub fn label<T>(grid: &[Vec<T>], same_region: impl Fn(&T, &T) -> bool) -> Self {
+        let mut labels: Vec<Vec<Option<usize>>> =
+            grid.iter().map(|row| vec![None; row.len()]).collect();
+        let mut cells = Vec::new();
+
+        for y in 0..grid.len() {
+            for x in 0..grid[y].len() {
+                if labels[y][x].is_some() {
+                    continue;
+                }
+                let label = cells.len();
+                let mut region = vec![(x, y)];
+                let mut stack = vec![(x, y)];
+                labels[y][x] = Some(label);
+                while let Some((cx, cy)) = stack.pop() {
+                    for (nx, ny) in neighbours(grid, (cx, cy)) {
+                        if labels[ny][nx].is_none() && same_region(&grid[cy][cx], &grid[ny][nx]) {
+                            labels[ny][nx] = Some(label);
+                            region.push((nx, ny));
+                            stack.push((nx, ny));
+                        }
+                    }
+                }
+                cells.push(region);
+            }
+        }
+
+        Regions {
+            labels: labels
+                .into_iter()
+                .map(|row| row.into_iter().map(Option::unwrap).collect())
+                .collect(),
+            cells,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.cells.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cells.is_empty()
+    }
+
+    // the region containing a position, or None for positions outside the grid
+    pub fn region_at(&self, (x, y): (i64, i64)) -> Option<usize> {
+        if x < 0 || y < 0 {
+            return None;
+        }
+        self.labels.get(y as usize)?.get(x as usize).copied()
+    }
+
+    // the cells of each region, indexed by label
+    pub fn cells(&self) -> &[Vec<(usize, usize)>] {
+        &self.cells
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_label() {
+        let grid = vec![
+            "OOOOO".chars().collect::<Vec<char>>(),
+            "OXOXO".chars().collect(),
+            "OOOOO".chars().collect(),
+        ];
+        let regions = Regions::label(&grid, |a, b| a == b);
+        assert_eq!(regions.len(), 3);
+        assert_eq!(regions.region_at((0, 0)), Some(0));
+        assert_eq!(regions.region_at((1, 1)), Some(1));
+        assert_eq!(regions.region_at((3, 1)), Some(2));
+        assert_eq!(regions.region_at((5, 0)), None);
+        assert_eq!(regions.region_at((-1, 0)), None);
+        assert_eq!(regions.cells()[0].len(), 13);
+    }
+
+    #[test]
+    fn test_label_custom_connection() {
+        let grid = vec![vec![1, 2, 4, 9], vec![8, 3, 5, 6]];
+        let regions = Regions::label(&grid, |a: &i32, b: &i32| (a - b).abs() == 1);
+        assert_eq!(regions.len(), 4);
+        assert_eq!(regions.cells()[0], vec![(0, 0), (1, 0), (1, 1)]);
+    }
+}